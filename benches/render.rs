@@ -0,0 +1,96 @@
+//! Rendering benchmarks over typical workloads.
+//!
+//! Run with `cargo bench`, add `--features rayon` to measure the parallel painter.
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use ratatui::Terminal;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use soft_ratatui::SoftBackend;
+use std::hint::black_box;
+
+static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+
+/// Grid sizes in cells and scale factors, from a small panel up to a large high-DPI window.
+const WORKLOADS: [(u16, u16, f32); 3] = [(80, 24, 1.0), (200, 60, 1.0), (300, 100, 2.0)];
+
+fn terminal(width: u16, height: u16, scale_factor: f32) -> Terminal<SoftBackend> {
+    let backend = SoftBackend::new_with_font_and_scale(width, height, 16.0, FONT_DATA, scale_factor);
+    Terminal::new(backend).unwrap()
+}
+
+/// Draws a bordered paragraph filling the screen, `frame` changes part of the text.
+fn draw_screen(terminal: &mut Terminal<SoftBackend>, frame: usize) {
+    terminal
+        .draw(|f| {
+            let area = f.area();
+            let lines: Vec<Line> = (0..area.height)
+                .map(|row| {
+                    let style = Style::default()
+                        .fg(Color::Indexed((row as usize * 7 % 256) as u8))
+                        .bg(if row % 2 == 0 { Color::Reset } else { Color::DarkGray });
+                    let style = if row % 5 == 0 {
+                        style.add_modifier(Modifier::BOLD)
+                    } else {
+                        style
+                    };
+                    Line::from(vec![
+                        Span::styled(format!("{row:>4} frame {frame:>6} "), style),
+                        Span::raw("The quick brown fox jumps over the lazy dog. ".repeat(8)),
+                    ])
+                })
+                .collect();
+            f.render_widget(
+                Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title("bench")),
+                area,
+            );
+        })
+        .unwrap();
+}
+
+fn full_redraw(c: &mut Criterion) {
+    let mut group = c.benchmark_group("full_redraw");
+    for (width, height, scale) in WORKLOADS {
+        let mut terminal = terminal(width, height, scale);
+        draw_screen(&mut terminal, 0);
+        group.bench_function(BenchmarkId::from_parameter(format!("{width}x{height}@{scale}")), |b| {
+            b.iter(|| terminal.backend_mut().redraw())
+        });
+    }
+    group.finish();
+}
+
+fn cold_redraw(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cold_redraw");
+    group.sample_size(10);
+    for (width, height, scale) in WORKLOADS {
+        let mut terminal = terminal(width, height, scale);
+        draw_screen(&mut terminal, 0);
+        group.bench_function(BenchmarkId::from_parameter(format!("{width}x{height}@{scale}")), |b| {
+            b.iter(|| {
+                let backend = terminal.backend_mut();
+                // Changing the font size drops every cache before redrawing.
+                backend.set_font_size(16.0);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn incremental_draw(c: &mut Criterion) {
+    let mut group = c.benchmark_group("incremental_draw");
+    for (width, height, scale) in WORKLOADS {
+        let mut terminal = terminal(width, height, scale);
+        let mut frame = 0;
+        group.bench_function(BenchmarkId::from_parameter(format!("{width}x{height}@{scale}")), |b| {
+            b.iter(|| {
+                frame += 1;
+                draw_screen(black_box(&mut terminal), frame);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, full_redraw, cold_redraw, incremental_draw);
+criterion_main!(benches);
@@ -9,7 +9,6 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 use soft_ratatui::SoftBackend;
-static FONT_DATA: &[u8] = include_bytes!("../../assets/iosevka.ttf");
 
 fn main() {
     App::new()
@@ -90,8 +89,8 @@ fn ui_example_system(
 struct SoftTerminal(Terminal<SoftBackend>);
 impl Default for SoftTerminal {
     fn default() -> Self {
-        let mut backend = SoftBackend::new_with_font(15, 15, 16, FONT_DATA);
-        //backend.set_font_size(12);
+        let mut backend = SoftBackend::new_default(15, 15, 16.0);
+        //backend.set_font_size(12.0);
         Self(Terminal::new(backend).unwrap())
     }
 }
@@ -39,11 +39,12 @@ struct MyApp {
     pub terminal: Terminal<SoftBackend>,
     pub text_ref: Option<TextureHandle>,
     pub appik: App,
+    pub last_tick: Instant,
 }
 
 impl MyApp {
     fn new() -> Self {
-        let backend = SoftBackend::new_with_system_fonts(100, 50, 12);
+        let backend = SoftBackend::new_with_system_fonts(100, 50, 12.0);
         let mut terminal = Terminal::new(backend).unwrap();
         let appik = App::default();
 
@@ -51,6 +52,7 @@ impl MyApp {
             terminal,
             text_ref: None,
             appik,
+            last_tick: Instant::now(),
         }
     }
 }
@@ -59,9 +61,9 @@ impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // terminal.draw(draw).expect("failed to draw frame");
         self.appik.run(&mut self.terminal);
-        let sizeik = (self.terminal.backend().blink_counter as i32) % 30 + 1;
-
-        // self.terminal.backend_mut().set_font_size(sizeik);
+        let now = Instant::now();
+        self.terminal.backend_mut().tick(now - self.last_tick);
+        self.last_tick = now;
 
         let colorik = egui::ColorImage::from_rgb(
             [
@@ -5,7 +5,7 @@ use soft_ratatui::SoftBackend;
 
 fn main() {
     // Create backend with 2.0 scale factor for high-DPI displays (e.g., Retina displays)
-    let backend = SoftBackend::new_with_system_fonts_and_scale(100, 50, 16, 2.0);
+    let backend = SoftBackend::new_with_system_fonts_and_scale(100, 50, 16.0, 2.0);
     let mut terminal = Terminal::new(backend).unwrap();
     terminal.clear().unwrap();
 
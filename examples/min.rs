@@ -1,13 +1,12 @@
 /// A minimal example of a Ratatui application.
-use ratatui::text::Text;
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
-use ratatui::{Frame, Terminal};
+use ratatui::Terminal;
 use soft_ratatui::SoftBackend;
 
 fn main() {
-    let backend = SoftBackend::new_with_system_fonts(100, 50, 16);
+    let backend = SoftBackend::new_with_system_fonts(100, 50, 16.0);
     let mut terminal = Terminal::new(backend).unwrap();
-    terminal.clear();
+    terminal.clear().unwrap();
 
     // terminal.draw(draw).expect("failed to draw frame");
     terminal.draw(|frame| {
@@ -19,5 +18,6 @@ fn main() {
                 .wrap(Wrap { trim: false }),
             area,
         );
-    });
+    })
+    .unwrap();
 }
@@ -0,0 +1,371 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::collections::HashMap;
+use crate::cp437::CP437;
+use crate::glyph_renderer::{CellText, GlyphContent, GlyphImage, GlyphRenderer};
+use crate::math;
+use crate::pixmap::{RgbPixmap, upscale};
+
+use ratatui_core::style::Modifier;
+
+/// Largest glyph width and height accepted from font files, larger glyphs are rejected as malformed
+/// instead of allocating huge masks.
+const MAX_GLYPH_SIZE: usize = 256;
+
+/// A fixed-size bitmap font, drawn pixel for pixel instead of being rasterized by cosmic-text.
+/// Every glyph is stored as a cell-sized coverage mask with one byte per pixel.
+///
+/// Supported sources are BDF files, PSF1/PSF2 console fonts and sprite sheets laid out as a grid.
+/// PCF fonts can be converted to BDF with `pcf2bdf` first.
+#[derive(Debug, Clone)]
+pub struct BitmapFont {
+    glyph_width: usize,
+    glyph_height: usize,
+    glyphs: HashMap<char, Vec<u8>>,
+    /// Whole number of pixels per font pixel, derived from the scale factor.
+    scale: usize,
+}
+
+/// Why a bitmap font could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitmapFontError {
+    /// The data does not start with a known magic number or header.
+    UnknownFormat,
+    /// The data ends before all glyphs described by the header.
+    Truncated,
+    /// A BDF property or bitmap line could not be parsed.
+    InvalidBdf(String),
+    /// The glyph size is zero, larger than font files may declare, or does not divide the sprite sheet.
+    InvalidGlyphSize,
+}
+
+impl fmt::Display for BitmapFontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitmapFontError::UnknownFormat => write!(f, "unknown bitmap font format"),
+            BitmapFontError::Truncated => write!(f, "bitmap font data is truncated"),
+            BitmapFontError::InvalidBdf(line) => write!(f, "invalid BDF line: {line}"),
+            BitmapFontError::InvalidGlyphSize => write!(f, "invalid glyph size"),
+        }
+    }
+}
+
+impl core::error::Error for BitmapFontError {}
+
+impl BitmapFont {
+    /// Returns the width of a glyph in pixels, before scaling.
+    pub fn glyph_width(&self) -> usize {
+        self.glyph_width
+    }
+
+    /// Returns the height of a glyph in pixels, before scaling.
+    pub fn glyph_height(&self) -> usize {
+        self.glyph_height
+    }
+
+    /// Returns the coverage mask of a character, `glyph_width * glyph_height` bytes from 0 to 255.
+    pub fn glyph(&self, c: char) -> Option<&[u8]> {
+        self.glyphs.get(&c).map(Vec::as_slice)
+    }
+
+    /// Loads a font in the Glyph Bitmap Distribution Format.
+    /// Glyph encodings are taken as Unicode code points, which holds for ISO10646 and ISO8859-1 fonts.
+    pub fn from_bdf(source: &str) -> Result<Self, BitmapFontError> {
+        let invalid = |line: &str| BitmapFontError::InvalidBdf(line.to_string());
+        // Values are 32-bit in the format and widened so that sums of them cannot overflow.
+        let numbers = |line: &str, count: usize| -> Result<Vec<i64>, BitmapFontError> {
+            let values: Vec<i64> = line
+                .split_whitespace()
+                .skip(1)
+                .map(|value| value.parse::<i32>().map(i64::from).map_err(|_| invalid(line)))
+                .collect::<Result<_, _>>()?;
+            if values.len() < count {
+                return Err(invalid(line));
+            }
+            Ok(values)
+        };
+
+        let mut lines = source.lines().map(str::trim);
+        let mut bounding_box = None;
+        let mut glyphs = HashMap::new();
+        // Encoding and bounding box of the glyph being read.
+        let mut encoding = None;
+        let mut glyph_box = None;
+
+        while let Some(line) = lines.next() {
+            let keyword = line.split_whitespace().next().unwrap_or("");
+            match keyword {
+                "FONTBOUNDINGBOX" => {
+                    let font_box = numbers(line, 4)?;
+                    if !(1..=MAX_GLYPH_SIZE as i64).contains(&font_box[0])
+                        || !(1..=MAX_GLYPH_SIZE as i64).contains(&font_box[1])
+                    {
+                        return Err(BitmapFontError::InvalidGlyphSize);
+                    }
+                    bounding_box = Some(font_box);
+                }
+                "STARTCHAR" => {
+                    encoding = None;
+                    glyph_box = None;
+                }
+                "ENCODING" => encoding = Some(numbers(line, 1)?[0]),
+                "BBX" => glyph_box = Some(numbers(line, 4)?),
+                "BITMAP" => {
+                    let font_box = bounding_box.as_ref().ok_or_else(|| invalid(line))?;
+                    let glyph_box = glyph_box.clone().unwrap_or_else(|| font_box.clone());
+                    let (width, height) = (font_box[0] as usize, font_box[1] as usize);
+                    let mut mask = vec![0; width * height];
+                    // The top row of the glyph, counted from the top of the font bounding box.
+                    let top = (font_box[1] + font_box[3]) - (glyph_box[1] + glyph_box[3]);
+                    let left = glyph_box[2] - font_box[2];
+                    for row in 0..glyph_box[1] {
+                        let hex = lines.next().ok_or(BitmapFontError::Truncated)?;
+                        let y = top + row;
+                        for column in 0..glyph_box[0] {
+                            let nibble = hex
+                                .get(column as usize / 4..column as usize / 4 + 1)
+                                .and_then(|digit| u8::from_str_radix(digit, 16).ok())
+                                .ok_or_else(|| invalid(hex))?;
+                            let x = left + column;
+                            let set = nibble & (0b1000 >> (column % 4)) != 0;
+                            if set && (0..width as i64).contains(&x) && (0..height as i64).contains(&y) {
+                                mask[y as usize * width + x as usize] = 255;
+                            }
+                        }
+                    }
+                    if let Some(c) = encoding
+                        .and_then(|encoding| u32::try_from(encoding).ok())
+                        .and_then(char::from_u32)
+                    {
+                        glyphs.insert(c, mask);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let font_box = bounding_box.ok_or(BitmapFontError::UnknownFormat)?;
+        Self::with_glyphs(font_box[0] as usize, font_box[1] as usize, glyphs)
+    }
+
+    /// Loads a PC Screen Font, the console font format of Linux, in either version 1 or 2.
+    /// Fonts without a Unicode table are assumed to follow code page 437.
+    pub fn from_psf(data: &[u8]) -> Result<Self, BitmapFontError> {
+        let u32_at = |offset: usize| -> Result<usize, BitmapFontError> {
+            let bytes = data.get(offset..offset + 4).ok_or(BitmapFontError::Truncated)?;
+            Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+        };
+
+        let (width, height, count, glyph_start, glyph_bytes, has_table, utf8_table) = match data {
+            [0x36, 0x04, mode, charsize, ..] => {
+                let count = if mode & 0x01 != 0 { 512 } else { 256 };
+                let has_table = mode & 0x06 != 0;
+                (8, *charsize as usize, count, 4, *charsize as usize, has_table, false)
+            }
+            [0x72, 0xb5, 0x4a, 0x86, ..] => {
+                let header_size = u32_at(8)?;
+                let flags = u32_at(12)?;
+                let count = u32_at(16)?;
+                let glyph_bytes = u32_at(20)?;
+                let height = u32_at(24)?;
+                let width = u32_at(28)?;
+                (width, height, count, header_size, glyph_bytes, flags & 1 != 0, true)
+            }
+            _ => return Err(BitmapFontError::UnknownFormat),
+        };
+
+        if !(1..=MAX_GLYPH_SIZE).contains(&width) || !(1..=MAX_GLYPH_SIZE).contains(&height) {
+            return Err(BitmapFontError::InvalidGlyphSize);
+        }
+        let row_bytes = width.div_ceil(8);
+        if glyph_bytes < row_bytes * height {
+            return Err(BitmapFontError::InvalidGlyphSize);
+        }
+        // Header fields of PSF2 are untrusted, a table that cannot be addressed cannot be in the data either.
+        let table_start = count
+            .checked_mul(glyph_bytes)
+            .and_then(|bitmaps| bitmaps.checked_add(glyph_start))
+            .ok_or(BitmapFontError::Truncated)?;
+        let bitmaps = data
+            .get(glyph_start..table_start)
+            .ok_or(BitmapFontError::Truncated)?;
+
+        let masks: Vec<Vec<u8>> = bitmaps
+            .chunks_exact(glyph_bytes)
+            .map(|bitmap| {
+                let mut mask = vec![0; width * height];
+                for y in 0..height {
+                    for x in 0..width {
+                        if bitmap[y * row_bytes + x / 8] & (0x80 >> (x % 8)) != 0 {
+                            mask[y * width + x] = 255;
+                        }
+                    }
+                }
+                mask
+            })
+            .collect();
+
+        let mut glyphs = HashMap::new();
+        if has_table {
+            let mut table = &data[table_start..];
+            for mask in &masks {
+                let chars = if utf8_table {
+                    // UTF-8 characters terminated by 0xFF, sequences after 0xFE are skipped.
+                    let end = table.iter().position(|&b| b == 0xFF).unwrap_or(table.len());
+                    let entry = &table[..end];
+                    table = table.get(end + 1..).unwrap_or_default();
+                    let singles = entry.split(|&b| b == 0xFE).next().unwrap_or_default();
+                    String::from_utf8_lossy(singles).chars().collect::<Vec<char>>()
+                } else {
+                    // UCS-2 values terminated by 0xFFFF, sequences after 0xFFFE are skipped.
+                    let mut chars = Vec::new();
+                    let mut in_sequence = false;
+                    while let [low, high, rest @ ..] = table {
+                        table = rest;
+                        match u16::from_le_bytes([*low, *high]) {
+                            0xFFFF => break,
+                            0xFFFE => in_sequence = true,
+                            value if !in_sequence => chars.extend(char::from_u32(value as u32)),
+                            _ => {}
+                        }
+                    }
+                    chars
+                };
+                for c in chars {
+                    glyphs.entry(c).or_insert_with(|| mask.clone());
+                }
+            }
+        } else {
+            for (&c, mask) in CP437.iter().zip(&masks) {
+                glyphs.insert(c, mask.clone());
+            }
+        }
+
+        Self::with_glyphs(width, height, glyphs)
+    }
+
+    /// Cuts a sprite sheet into a grid of glyphs, assigning the characters row by row.
+    /// Glyphs are expected to be light on a dark background, the luminance of a pixel becomes its coverage.
+    pub fn from_sprite_sheet(
+        sheet: &RgbPixmap,
+        glyph_width: usize,
+        glyph_height: usize,
+        chars: impl IntoIterator<Item = char>,
+    ) -> Result<Self, BitmapFontError> {
+        if glyph_width == 0
+            || glyph_height == 0
+            || !sheet.width().is_multiple_of(glyph_width)
+            || !sheet.height().is_multiple_of(glyph_height)
+        {
+            return Err(BitmapFontError::InvalidGlyphSize);
+        }
+        let columns = sheet.width() / glyph_width;
+        let tiles = columns * (sheet.height() / glyph_height);
+
+        let mut glyphs = HashMap::new();
+        for (index, c) in chars.into_iter().take(tiles).enumerate() {
+            let (left, top) = (index % columns * glyph_width, index / columns * glyph_height);
+            let mut mask = Vec::with_capacity(glyph_width * glyph_height);
+            for y in top..top + glyph_height {
+                for x in left..left + glyph_width {
+                    let [r, g, b] = sheet.get_pixel(x, y);
+                    let luminance = (r as u32 * 54 + g as u32 * 183 + b as u32 * 19) >> 8;
+                    mask.push(luminance as u8);
+                }
+            }
+            glyphs.entry(c).or_insert(mask);
+        }
+        Self::with_glyphs(glyph_width, glyph_height, glyphs)
+    }
+
+    /// Cuts a sprite sheet of 16 by 16 glyphs in code page 437 order, the usual layout of roguelike fonts.
+    pub fn from_cp437_sprite_sheet(sheet: &RgbPixmap) -> Result<Self, BitmapFontError> {
+        Self::from_sprite_sheet(sheet, sheet.width() / 16, sheet.height() / 16, CP437)
+    }
+
+    fn with_glyphs(
+        glyph_width: usize,
+        glyph_height: usize,
+        glyphs: HashMap<char, Vec<u8>>,
+    ) -> Result<Self, BitmapFontError> {
+        if glyph_width == 0 || glyph_height == 0 {
+            return Err(BitmapFontError::InvalidGlyphSize);
+        }
+        Ok(Self {
+            glyph_width,
+            glyph_height,
+            glyphs,
+            scale: 1,
+        })
+    }
+
+    /// Draws the text of a cell into a coverage mask of `glyph_width * scale` by `glyph_height * scale` pixels.
+    /// Underline and strikeout combining marks become lines, bold smears the glyph one pixel to the right
+    /// and italic shears it, all before scaling so that the pixels stay square.
+    fn render_mask(&self, symbol: &str, bold: bool, italic: bool, scale: usize) -> Vec<u8> {
+        let (width, height) = (self.glyph_width, self.glyph_height);
+        let mut mask = vec![0u8; width * height];
+
+        let base = symbol.chars().find(|c| !matches!(c, '\u{0332}' | '\u{0336}'));
+        if let Some(glyph) = base
+            .filter(|c| *c != ' ')
+            .and_then(|c| self.glyph(c).or_else(|| self.glyph('?')))
+        {
+            for y in 0..height {
+                // Leans the top of the glyph to the right, one pixel for every four rows.
+                let shift = if italic { (height - 1 - y) / 4 } else { 0 };
+                for x in shift..width {
+                    let mut coverage = glyph[y * width + x - shift];
+                    if bold && x > shift {
+                        coverage = coverage.max(glyph[y * width + x - shift - 1]);
+                    }
+                    mask[y * width + x] = coverage;
+                }
+            }
+        }
+        if symbol.contains('\u{0332}') {
+            mask[(height - 1) * width..].fill(255);
+        }
+        if symbol.contains('\u{0336}') {
+            mask[height / 2 * width..(height / 2 + 1) * width].fill(255);
+        }
+
+        upscale(&mask, width, 1, scale)
+    }
+}
+
+impl GlyphRenderer for BitmapFont {
+    /// Bitmap fonts only scale by whole pixels to stay crisp, the font size is ignored.
+    fn set_size(&mut self, _font_size: f32, scale_factor: f32) -> (usize, usize) {
+        self.scale = (math::round(scale_factor) as usize).max(1);
+        (self.glyph_width * self.scale, self.glyph_height * self.scale)
+    }
+
+    fn render(&mut self, text: &CellText) -> Vec<GlyphImage> {
+        let mask = self.render_mask(
+            text.symbol,
+            text.modifier.contains(Modifier::BOLD),
+            text.modifier.contains(Modifier::ITALIC),
+            self.scale,
+        );
+        if mask.iter().all(|&coverage| coverage == 0) {
+            return Vec::new();
+        }
+        vec![GlyphImage {
+            left: 0,
+            top: 0,
+            width: self.glyph_width * self.scale,
+            height: self.glyph_height * self.scale,
+            content: GlyphContent::Mask,
+            data: mask,
+        }]
+    }
+
+    fn fork(&self) -> Option<Box<dyn GlyphRenderer>> {
+        Some(Box::new(self.clone()))
+    }
+}
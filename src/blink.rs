@@ -0,0 +1,48 @@
+use core::time::Duration;
+
+/// Timing of something that blinks, such as text with a blink modifier or the cursor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlinkTiming {
+    /// Length of one full on and off cycle.
+    pub period: Duration,
+    /// Share of the period that is visible, from 0.0 to 1.0. Every cycle starts visible.
+    pub duty_cycle: f32,
+}
+
+impl BlinkTiming {
+    /// Slow blink (SGR 5), about once per second.
+    pub const SLOW: Self = Self::new(Duration::from_millis(1000), 0.5);
+    /// Rapid blink (SGR 6), about three times per second.
+    pub const RAPID: Self = Self::new(Duration::from_millis(333), 0.5);
+    /// Cursor blink, matching the 600 ms on and off times of xterm.
+    pub const CURSOR: Self = Self::new(Duration::from_millis(1200), 0.5);
+
+    pub const fn new(period: Duration, duty_cycle: f32) -> Self {
+        Self { period, duty_cycle }
+    }
+
+    /// Returns how long each cycle stays visible, clamped to the period.
+    fn on_time(&self) -> u128 {
+        let period = self.period.as_nanos();
+        ((period as f64 * self.duty_cycle.clamp(0.0, 1.0) as f64) as u128).min(period)
+    }
+
+    /// Returns whether the blinking element is visible `elapsed` after the blinking started.
+    pub fn is_visible(&self, elapsed: Duration) -> bool {
+        let period = self.period.as_nanos();
+        period == 0 || elapsed.as_nanos() % period < self.on_time()
+    }
+
+    /// Returns the time from `elapsed` until the element next turns on or off,
+    /// `None` when it never changes.
+    pub fn next_change(&self, elapsed: Duration) -> Option<Duration> {
+        let period = self.period.as_nanos();
+        let on_time = self.on_time();
+        if period == 0 || on_time == 0 || on_time == period {
+            return None;
+        }
+        let phase = elapsed.as_nanos() % period;
+        let remaining = if phase < on_time { on_time - phase } else { period - phase };
+        Some(Duration::from_nanos(remaining as u64))
+    }
+}
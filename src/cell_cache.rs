@@ -0,0 +1,335 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
+use crate::pixmap::{PixelRect, PixmapBand};
+use crate::subpixel::Antialiasing;
+
+use ratatui_core::style::Modifier;
+
+/// Default memory budget of the rendered cell cache, in bytes.
+pub const DEFAULT_CELL_CACHE_BYTES: usize = 8 * 1024 * 1024;
+
+/// A rectangle of opaque rgb pixels, positioned relative to the origin of a cell.
+#[derive(Debug, Clone)]
+pub struct PixelBlock {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+/// The rendered text of a cell, stored as the glyph images in the order they are drawn.
+/// Pixels outside of the glyph images are left untouched, so text can overflow into neighbouring cells.
+#[derive(Debug, Clone, Default)]
+pub struct RenderedCell {
+    pub blocks: Vec<PixelBlock>,
+}
+
+impl RenderedCell {
+    /// Memory used by the pixel data of the cell.
+    pub fn byte_size(&self) -> usize {
+        self.blocks.iter().map(|block| block.data.len()).sum()
+    }
+
+    /// Column right of and row below the rendered pixels, relative to the cell origin.
+    pub fn extent(&self) -> (usize, usize) {
+        self.blocks.iter().fold((0, 0), |(right, bottom), block| {
+            (right.max(block.x + block.width), bottom.max(block.y + block.height))
+        })
+    }
+
+    /// Copies the rendered pixels into the band with the cell origin at (x, y).
+    /// Pixels outside of `clip` are skipped.
+    pub fn blit(&self, band: &mut PixmapBand, x: usize, y: usize, clip: PixelRect) {
+        for block in &self.blocks {
+            let left = x + block.x;
+            let top = y + block.y;
+            let visible_left = left.max(clip.x);
+            let visible_top = top.max(clip.y);
+            let visible_right = (left + block.width).min(clip.x + clip.width);
+            let visible_bottom = (top + block.height).min(clip.y + clip.height);
+            if visible_left >= visible_right || visible_top >= visible_bottom {
+                continue;
+            }
+            let skipped = 3 * ((visible_top - top) * block.width + visible_left - left);
+            band.copy_from(
+                visible_left,
+                visible_top,
+                block.width,
+                visible_right - visible_left,
+                visible_bottom - visible_top,
+                &block.data[skipped..],
+            );
+        }
+    }
+}
+
+/// Everything that influences how the text of a cell looks once rendered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CellKey {
+    pub symbol: String,
+    pub modifier: Modifier,
+    pub fg: [u8; 3],
+    pub bg: [u8; 3],
+    pub antialiasing: Antialiasing,
+}
+
+impl CellKey {
+    /// Creates a key keeping only the modifiers that change the glyphs, bold and italic.
+    /// Underlines, strikethrough and the like are drawn over the cached text.
+    pub fn new(symbol: String, modifier: Modifier, fg: [u8; 3], bg: [u8; 3], antialiasing: Antialiasing) -> Self {
+        Self {
+            symbol,
+            modifier: modifier & (Modifier::BOLD | Modifier::ITALIC),
+            fg,
+            bg,
+            antialiasing,
+        }
+    }
+}
+
+/// Bookkeeping cost of a cache entry on top of its pixels, so that empty cells are not free.
+const ENTRY_OVERHEAD_BYTES: usize = 64;
+
+#[derive(Debug)]
+struct CacheEntry {
+    cell: Arc<RenderedCell>,
+    size: usize,
+    last_used: u64,
+}
+
+/// A least-recently-used cache of rendered cells, bounded by the memory used by their pixels.
+#[derive(Debug)]
+pub struct CellCache {
+    entries: HashMap<CellKey, CacheEntry>,
+    /// Keys of all entries by the tick they were last used at, the least recently used first.
+    recency: BTreeMap<u64, CellKey>,
+    used_bytes: usize,
+    capacity_bytes: usize,
+    clock: u64,
+}
+
+impl CellCache {
+    pub fn new(capacity_bytes: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            used_bytes: 0,
+            capacity_bytes,
+            clock: 0,
+        }
+    }
+
+    /// Returns the cached cell and marks it as recently used.
+    pub fn get(&mut self, key: &CellKey) -> Option<Arc<RenderedCell>> {
+        let entry = self.entries.get_mut(key)?;
+        self.clock += 1;
+        if let Some(key) = self.recency.remove(&entry.last_used) {
+            self.recency.insert(self.clock, key);
+        }
+        entry.last_used = self.clock;
+        Some(entry.cell.clone())
+    }
+
+    /// Returns true when the cell is cached, without marking it as used.
+    #[cfg(feature = "rayon")]
+    pub fn contains(&self, key: &CellKey) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Stores a rendered cell, evicting the least recently used cells when over capacity.
+    /// Cells larger than the whole capacity are not stored.
+    pub fn insert(&mut self, key: CellKey, cell: Arc<RenderedCell>) {
+        let size = cell.byte_size() + key.symbol.len() + ENTRY_OVERHEAD_BYTES;
+        if size > self.capacity_bytes {
+            return;
+        }
+        self.clock += 1;
+        let entry = CacheEntry {
+            cell,
+            size,
+            last_used: self.clock,
+        };
+        self.recency.insert(self.clock, key.clone());
+        if let Some(old) = self.entries.insert(key, entry) {
+            self.recency.remove(&old.last_used);
+            self.used_bytes -= old.size;
+        }
+        self.used_bytes += size;
+        if self.used_bytes > self.capacity_bytes {
+            self.evict();
+        }
+    }
+
+    /// Drops the oldest cells until a quarter of the capacity is free again,
+    /// so that eviction does not run on every insert once the cache is full.
+    fn evict(&mut self) {
+        let target = self.capacity_bytes / 4 * 3;
+        while self.used_bytes > target {
+            let Some((_, key)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&key) {
+                self.used_bytes -= entry.size;
+            }
+        }
+    }
+
+    /// Sets the memory budget in bytes, a capacity of 0 disables caching.
+    pub fn set_capacity(&mut self, capacity_bytes: usize) {
+        self.capacity_bytes = capacity_bytes;
+        if self.used_bytes > self.capacity_bytes {
+            self.evict();
+        }
+    }
+
+    /// Removes all cells, needed whenever the cell metrics change.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.used_bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use ratatui_core::backend::Backend;
+    use ratatui_core::buffer::Cell;
+    use ratatui_core::style::{Color, Style};
+
+    use super::*;
+    use crate::{BitmapFont, SoftBackend};
+
+    /// Pixels of the cells created by [`cell`].
+    const PIXELS: usize = 11;
+    /// Bytes used by an entry of [`key`] and [`cell`].
+    const ENTRY_BYTES: usize = 3 * PIXELS + 1 + ENTRY_OVERHEAD_BYTES;
+
+    /// An 8x8 font whose `A` has slanted edges, so bold and italic change its pixels.
+    const BDF: &str = "\
+STARTFONT 2.1
+FONTBOUNDINGBOX 8 8 0 0
+STARTCHAR A
+ENCODING 65
+BBX 8 8 0 0
+BITMAP
+00
+18
+24
+42
+7E
+42
+42
+00
+ENDCHAR
+ENDFONT
+";
+
+    /// A key with a one letter symbol.
+    fn key(index: u8, modifier: Modifier) -> CellKey {
+        let symbol = char::from(b'a' + index).to_string();
+        CellKey::new(symbol, modifier, [255, 255, 255], [0, 0, 0], Antialiasing::Grayscale)
+    }
+
+    fn cell() -> Arc<RenderedCell> {
+        Arc::new(RenderedCell {
+            blocks: vec![PixelBlock {
+                x: 0,
+                y: 0,
+                width: PIXELS,
+                height: 1,
+                data: vec![0; 3 * PIXELS],
+            }],
+        })
+    }
+
+    fn filled(count: u8) -> CellCache {
+        let mut cache = CellCache::new(10 * ENTRY_BYTES);
+        for index in 0..count {
+            cache.insert(key(index, Modifier::empty()), cell());
+        }
+        cache
+    }
+
+    fn cached(cache: &mut CellCache, count: u8) -> Vec<u8> {
+        (0..count).filter(|&index| cache.get(&key(index, Modifier::empty())).is_some()).collect()
+    }
+
+    #[test]
+    fn eviction_frees_a_quarter_of_the_capacity() {
+        let mut cache = filled(10);
+        assert_eq!(cache.used_bytes, 10 * ENTRY_BYTES);
+        cache.insert(key(10, Modifier::empty()), cell());
+        // The four oldest entries make room, three quarters of the capacity stay in use at most.
+        assert_eq!(cache.used_bytes, 7 * ENTRY_BYTES);
+        assert!(cache.used_bytes <= cache.capacity_bytes / 4 * 3);
+        assert_eq!((cache.entries.len(), cache.recency.len()), (7, 7));
+        assert_eq!(cached(&mut cache, 11), [4, 5, 6, 7, 8, 9, 10]);
+
+        cache.set_capacity(0);
+        assert_eq!((cache.used_bytes, cache.entries.len(), cache.recency.len()), (0, 0, 0));
+    }
+
+    #[test]
+    fn recently_used_entries_survive_eviction() {
+        let mut cache = filled(10);
+        cache.get(&key(0, Modifier::empty())).unwrap();
+        cache.get(&key(2, Modifier::empty())).unwrap();
+        // Replacing an entry counts as a use as well.
+        cache.insert(key(3, Modifier::empty()), cell());
+        cache.insert(key(10, Modifier::empty()), cell());
+        assert_eq!(cache.used_bytes, 7 * ENTRY_BYTES);
+        assert_eq!(cached(&mut cache, 11), [0, 2, 3, 7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn keys_ignore_modifiers_drawn_over_the_text() {
+        let styles = [Modifier::empty(), Modifier::BOLD, Modifier::ITALIC, Modifier::BOLD | Modifier::ITALIC];
+        let cells: Vec<Arc<RenderedCell>> = styles.iter().map(|_| cell()).collect();
+        let mut cache = CellCache::new(DEFAULT_CELL_CACHE_BYTES);
+        for (&modifier, cell) in styles.iter().zip(&cells) {
+            cache.insert(key(0, modifier), cell.clone());
+        }
+        assert_eq!(cache.entries.len(), styles.len());
+
+        let ignored = Modifier::UNDERLINED | Modifier::CROSSED_OUT | Modifier::SLOW_BLINK | Modifier::DIM;
+        for (&modifier, cell) in styles.iter().zip(&cells) {
+            assert_eq!(key(0, modifier | ignored), key(0, modifier));
+            assert!(Arc::ptr_eq(&cache.get(&key(0, modifier | ignored)).unwrap(), cell));
+        }
+    }
+
+    #[test]
+    fn cache_hits_match_a_cold_render() {
+        let draw = |backend: &mut SoftBackend| {
+            let styles = [Modifier::empty(), Modifier::BOLD, Modifier::ITALIC, Modifier::BOLD | Modifier::ITALIC];
+            let cells: Vec<Cell> = styles
+                .into_iter()
+                .map(|modifier| {
+                    let mut cell = Cell::new("A");
+                    cell.set_style(Style::new().fg(Color::Yellow).bg(Color::Blue).add_modifier(modifier));
+                    cell
+                })
+                .collect();
+            backend.draw(cells.iter().enumerate().map(|(x, cell)| (x as u16, 0, cell))).unwrap();
+        };
+        let font = BitmapFont::from_bdf(BDF).unwrap();
+
+        let mut cached = SoftBackend::new_with_bitmap_font(4, 1, font.clone(), 1.0);
+        draw(&mut cached);
+        // Every cell of the redraw is a cache hit.
+        cached.redraw();
+
+        let mut cold = SoftBackend::new_with_bitmap_font(4, 1, font, 1.0);
+        cold.set_cell_cache_capacity(0);
+        draw(&mut cold);
+        assert_eq!(cached.get_pixmap_data(), cold.get_pixmap_data());
+    }
+}
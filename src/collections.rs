@@ -0,0 +1,5 @@
+//! Hash maps and sets, taken from `hashbrown` in every build.
+//! Its default hasher is much faster than the SipHash of `std` for the small keys of the caches,
+//! which are looked up for every drawn cell.
+
+pub use hashbrown::{HashMap, HashSet};
@@ -1,4 +1,9 @@
-use ratatui::style::Color as RatColor;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::math;
+
+use ratatui_core::style::Color as RatColor;
 
 ///Converts a Ratatui Color into a rgb [u8;3]
 pub fn rat_to_rgb(rat_col: &RatColor, is_a_fg: bool) -> [u8; 3] {
@@ -27,38 +32,286 @@ pub fn rat_to_rgb(rat_col: &RatColor, is_a_fg: bool) -> [u8; 3] {
         RatColor::LightCyan => [224, 255, 255],
         RatColor::White => [255, 255, 255],
         RatColor::Indexed(i) => {
-            let i = *i as u8;
+            let i = *i;
             [i.wrapping_mul(i), i.wrapping_add(i), i]
         }
         RatColor::Rgb(r, g, b) => [*r, *g, *b],
     }
 }
 
-/// Blend two RGBA colors using alpha compositing.
-///
-/// (fg over bg) -> resulting RGB
+/// Divides a value in `0..=255 * 255` by 255, rounding to the nearest integer.
+#[inline(always)]
+fn div_255(value: u32) -> u32 {
+    let value = value + 128;
+    (value + (value >> 8)) >> 8
+}
+
+/// Blends a foreground color over an opaque background with the given coverage,
+/// using integer fixed-point arithmetic.
 ///
-/// * `fg` - [R, G, B, A] foreground color
-/// * `bg` - [R, G, B, A] background color
+/// (blend-coverage fg bg coverage) -> resulting RGB
 ///
-/// Returns: blended color as [u8; 4]
-pub fn blend_rgba(fg: [u8; 4], bg: [u8; 4]) -> [u8; 3] {
-    let fg_a = fg[3] as f32 / 255.0;
-    let bg_a = bg[3] as f32 / 255.0;
-    let out_a = fg_a + bg_a * (1.0 - fg_a);
-
-    let blend_channel =
-        |f: u8, b: u8| ((f as f32 * fg_a + b as f32 * bg_a * (1.0 - fg_a)) / out_a).round() as u8;
-
-    if out_a == 0.0 {
-        [0, 0, 0]
-    } else {
-        [
-            blend_channel(fg[0], bg[0]),
-            blend_channel(fg[1], bg[1]),
-            blend_channel(fg[2], bg[2]),
-        ]
+/// * `fg`       - [R, G, B] foreground color
+/// * `bg`       - [R, G, B] background color
+/// * `coverage` - 0 (only background) ..= 255 (only foreground)
+#[inline(always)]
+pub fn blend_coverage(fg: [u8; 3], bg: [u8; 3], coverage: u8) -> [u8; 3] {
+    let a = coverage as u32;
+    let inv = 255 - a;
+    [
+        div_255(fg[0] as u32 * a + bg[0] as u32 * inv) as u8,
+        div_255(fg[1] as u32 * a + bg[1] as u32 * inv) as u8,
+        div_255(fg[2] as u32 * a + bg[2] as u32 * inv) as u8,
+    ]
+}
+
+/// The color space glyph coverage is blended in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextBlending {
+    /// Blends the sRGB values directly, like most software rasterizers.
+    /// Light text on dark backgrounds looks thinner and dark text on light backgrounds heavier.
+    #[default]
+    Srgb,
+    /// Converts to linear light, blends, and converts back, giving text the same weight on any background.
+    Linear,
+}
+
+/// Precision of the linear light values used for blending.
+const LINEAR_BITS: u32 = 12;
+const LINEAR_MAX: u32 = (1 << LINEAR_BITS) - 1;
+
+/// Lookup tables converting between sRGB and linear light.
+#[derive(Debug, Clone)]
+struct GammaTables {
+    to_linear: [u16; 256],
+    to_srgb: [u8; LINEAR_MAX as usize + 1],
+}
+
+impl GammaTables {
+    fn new() -> Box<Self> {
+        let mut tables = Box::new(GammaTables {
+            to_linear: [0; 256],
+            to_srgb: [0; LINEAR_MAX as usize + 1],
+        });
+        for (value, linear) in tables.to_linear.iter_mut().enumerate() {
+            let c = value as f32 / 255.0;
+            let l = if c <= 0.04045 {
+                c / 12.92
+            } else {
+                math::powf((c + 0.055) / 1.055, 2.4)
+            };
+            *linear = math::round(l * LINEAR_MAX as f32) as u16;
+        }
+        for (value, srgb) in tables.to_srgb.iter_mut().enumerate() {
+            let l = value as f32 / LINEAR_MAX as f32;
+            let c = if l <= 0.0031308 {
+                l * 12.92
+            } else {
+                1.055 * math::powf(l, 1.0 / 2.4) - 0.055
+            };
+            *srgb = math::round(c * 255.0) as u8;
+        }
+        tables
+    }
+}
+
+/// Blends one channel of a foreground color over an opaque background in linear light.
+#[inline(always)]
+fn blend_channel_linear(f: u8, b: u8, coverage: u8, tables: &GammaTables) -> u8 {
+    let a = coverage as u32;
+    let f = tables.to_linear[f as usize] as u32;
+    let b = tables.to_linear[b as usize] as u32;
+    tables.to_srgb[((f * a + b * (255 - a) + 127) / 255) as usize]
+}
+
+/// Blends a foreground color over an opaque background in linear light.
+fn blend_coverage_linear(fg: [u8; 3], bg: [u8; 3], coverage: u8, tables: &GammaTables) -> [u8; 3] {
+    [
+        blend_channel_linear(fg[0], bg[0], coverage, tables),
+        blend_channel_linear(fg[1], bg[1], coverage, tables),
+        blend_channel_linear(fg[2], bg[2], coverage, tables),
+    ]
+}
+
+/// Turns glyph coverage into pixels: adjusts the coverage by a gamma and contrast curve,
+/// then blends the foreground over the background in the chosen color space.
+#[derive(Debug, Clone)]
+pub struct Compositor {
+    coverage: [u8; 256],
+    /// Only built for [`TextBlending::Linear`].
+    linear: Option<Box<GammaTables>>,
+}
+
+impl Default for Compositor {
+    fn default() -> Self {
+        Self::new(TextBlending::default(), 1.0, 1.0)
+    }
+}
+
+impl Compositor {
+    /// Gamma above 1.0 thickens strokes and below 1.0 thins them, contrast scales the adjusted coverage.
+    pub fn new(blending: TextBlending, gamma: f32, contrast: f32) -> Self {
+        let mut coverage = [0; 256];
+        for (value, adjusted) in coverage.iter_mut().enumerate() {
+            let c = math::powf(value as f32 / 255.0, 1.0 / gamma) * contrast;
+            *adjusted = math::round(c.clamp(0.0, 1.0) * 255.0) as u8;
+        }
+        let linear = (blending == TextBlending::Linear).then(GammaTables::new);
+        Self { coverage, linear }
+    }
+
+    /// Blends a whole row of glyph coverage, appending the resulting RGB pixels to `out`.
+    pub fn blend_row(&self, fg: [u8; 3], bg: [u8; 3], coverage: &[u8], out: &mut Vec<u8>) {
+        out.reserve(3 * coverage.len());
+        match &self.linear {
+            None => {
+                for &alpha in coverage {
+                    let alpha = self.coverage[alpha as usize];
+                    out.extend_from_slice(&blend_coverage(fg, bg, alpha));
+                }
+            }
+            Some(tables) => {
+                for &alpha in coverage {
+                    let alpha = self.coverage[alpha as usize];
+                    out.extend_from_slice(&blend_coverage_linear(fg, bg, alpha, tables));
+                }
+            }
+        }
+    }
+
+    /// Thresholds a row of glyph coverage, appending pure foreground or background pixels to `out`.
+    pub fn blend_row_aliased(&self, fg: [u8; 3], bg: [u8; 3], coverage: &[u8], out: &mut Vec<u8>) {
+        out.reserve(3 * coverage.len());
+        for &alpha in coverage {
+            let color = if self.coverage[alpha as usize] >= 128 { fg } else { bg };
+            out.extend_from_slice(&color);
+        }
+    }
+
+    /// Blends a row of RGBA pixels, such as color emoji, over the background by their alpha.
+    /// The coverage adjustments do not apply, they are meant for text only.
+    pub fn blend_row_color(&self, bg: [u8; 3], rgba: &[u8], out: &mut Vec<u8>) {
+        out.reserve(3 * (rgba.len() / 4));
+        for pixel in rgba.chunks_exact(4) {
+            out.extend_from_slice(&blend_coverage([pixel[0], pixel[1], pixel[2]], bg, pixel[3]));
+        }
+    }
+
+    /// Blends a row of a subpixel mask, which holds 4 bytes per pixel with one coverage value per color channel.
+    pub fn blend_row_subpixel(&self, fg: [u8; 3], bg: [u8; 3], mask: &[u8], out: &mut Vec<u8>) {
+        out.reserve(3 * (mask.len() / 4));
+        match &self.linear {
+            None => {
+                for pixel in mask.chunks_exact(4) {
+                    for (channel, &alpha) in pixel[..3].iter().enumerate() {
+                        let alpha = self.coverage[alpha as usize] as u32;
+                        let blended = fg[channel] as u32 * alpha + bg[channel] as u32 * (255 - alpha);
+                        out.push(div_255(blended) as u8);
+                    }
+                }
+            }
+            Some(tables) => {
+                for pixel in mask.chunks_exact(4) {
+                    for (channel, &alpha) in pixel[..3].iter().enumerate() {
+                        let alpha = self.coverage[alpha as usize];
+                        out.push(blend_channel_linear(fg[channel], bg[channel], alpha, tables));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Perceived brightness of a color (Rec. 601 weights), 0 for black and 255 for white.
+pub fn luminance(color: [u8; 3]) -> u8 {
+    ((color[0] as u32 * 77 + color[1] as u32 * 150 + color[2] as u32 * 29 + 128) >> 8) as u8
+}
+
+/// Maps a color to black or white, whichever is closer in luminance.
+pub fn mono_rgb(color: [u8; 3]) -> [u8; 3] {
+    if luminance(color) >= 128 { [255, 255, 255] } else { [0, 0, 0] }
+}
+
+/// Maps a foreground color to black or white like [`mono_rgb`], but never to the (already mapped) background,
+/// so text keeps readable even when its colors only differ in hue.
+pub fn mono_fg_rgb(fg: [u8; 3], mono_bg: [u8; 3]) -> [u8; 3] {
+    let fg = mono_rgb(fg);
+    if fg == mono_bg { [255 - fg[0], 255 - fg[1], 255 - fg[2]] } else { fg }
+}
+
+/// Converts a color like [`rat_to_rgb`], taking the named colors from `palette` when one is given.
+/// The palette lists the 16 named colors in ANSI order from `Black` to `White`, `Indexed` 0 to 15 map to the
+/// same entries, and `Reset` uses `Black` as background and `White` as foreground.
+pub fn palette_rgb(rat_col: &RatColor, is_a_fg: bool, palette: Option<&[[u8; 3]; 16]>) -> [u8; 3] {
+    let Some(palette) = palette else {
+        return rat_to_rgb(rat_col, is_a_fg);
+    };
+    let index = match rat_col {
+        RatColor::Reset => {
+            if is_a_fg {
+                15
+            } else {
+                0
+            }
+        }
+        RatColor::Black => 0,
+        RatColor::Red => 1,
+        RatColor::Green => 2,
+        RatColor::Yellow => 3,
+        RatColor::Blue => 4,
+        RatColor::Magenta => 5,
+        RatColor::Cyan => 6,
+        RatColor::Gray => 7,
+        RatColor::DarkGray => 8,
+        RatColor::LightRed => 9,
+        RatColor::LightGreen => 10,
+        RatColor::LightYellow => 11,
+        RatColor::LightBlue => 12,
+        RatColor::LightMagenta => 13,
+        RatColor::LightCyan => 14,
+        RatColor::White => 15,
+        RatColor::Indexed(i) if *i < 16 => *i as usize,
+        _ => return rat_to_rgb(rat_col, is_a_fg),
+    };
+    palette[index]
+}
+
+/// Relative luminance as defined by WCAG 2, 0.0 for black and 1.0 for white.
+fn relative_luminance(color: [u8; 3]) -> f32 {
+    let linear = |channel: u8| {
+        let c = channel as f32 / 255.0;
+        if c <= 0.04045 { c / 12.92 } else { math::powf((c + 0.055) / 1.055, 2.4) }
+    };
+    0.2126 * linear(color[0]) + 0.7152 * linear(color[1]) + 0.0722 * linear(color[2])
+}
+
+/// WCAG 2 contrast ratio between two colors, from 1.0 for equal colors to 21.0 for black and white.
+pub fn contrast_ratio(a: [u8; 3], b: [u8; 3]) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Moves a foreground color towards black or white, whichever contrasts more with the background,
+/// just far enough to reach `min_ratio`. Colors that already reach it are returned unchanged.
+pub fn enforce_contrast(fg: [u8; 3], bg: [u8; 3], min_ratio: f32) -> [u8; 3] {
+    if contrast_ratio(fg, bg) >= min_ratio {
+        return fg;
+    }
+    let target = if contrast_ratio([255; 3], bg) >= contrast_ratio([0; 3], bg) { [255; 3] } else { [0; 3] };
+    if contrast_ratio(target, bg) <= min_ratio {
+        return target;
+    }
+    // Binary search for the smallest step towards the target that is sufficient.
+    let (mut low, mut high) = (0u8, 255u8);
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if contrast_ratio(blend_coverage(target, fg, middle), bg) >= min_ratio {
+            high = middle;
+        } else {
+            low = middle;
+        }
     }
+    blend_coverage(target, fg, high)
 }
 
 pub fn dim_rgb(color: [u8; 3]) -> [u8; 3] {
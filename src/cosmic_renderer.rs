@@ -0,0 +1,286 @@
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
+use crate::glyph_renderer::{CellText, GlyphContent, GlyphImage, GlyphRenderer};
+use crate::subpixel::{Antialiasing, SubpixelCache};
+
+use cosmic_text::fontdb::{Database, ID as FontId};
+use cosmic_text::{
+    Attrs, AttrsList, Buffer as CosmicBuffer, CacheKey, CacheKeyFlags, Family, FontSystem,
+    LineEnding, Metrics, Shaping, SwashCache, SwashContent, Weight, Wrap,
+};
+use ratatui_core::style::Modifier;
+
+/// A small monospace font (a subset of Fira Mono covering ASCII) embedded into the binary by the `bundled-font` feature,
+/// so pixels are the same on every machine. Other characters are drawn as placeholder boxes.
+#[cfg(feature = "bundled-font")]
+pub const BUNDLED_FONT: &[u8] = include_bytes!("../assets/fm.ttf");
+
+/// The default [`GlyphRenderer`], shaping and rasterizing text with cosmic-text.
+/// Supports any font cosmic-text can load, with fallback fonts for missing characters.
+pub struct CosmicRenderer {
+    font_system: FontSystem,
+    cosmic_buffer: CosmicBuffer,
+    swash_cache: SwashCache,
+    subpixel_cache: SubpixelCache,
+    ascii_faces: HashMap<Modifier, Option<AsciiFace>>,
+    ascii_glyphs: HashMap<(char, Modifier), Option<PlacedGlyph>>,
+    ascii_fast_path: bool,
+}
+
+/// The primary font and baseline used to draw single ASCII characters without shaping.
+#[derive(Debug, Clone, Copy)]
+struct AsciiFace {
+    font_id: FontId,
+    line_y: f32,
+    flags: CacheKeyFlags,
+}
+
+/// A glyph ready to be rasterized, positioned relative to the top-left corner of its cell.
+#[derive(Debug, Clone, Copy)]
+struct PlacedGlyph {
+    cache_key: CacheKey,
+    x: i32,
+    y: i32,
+}
+
+fn cell_attrs(modifier: Modifier) -> Attrs<'static> {
+    let mut attrs = Attrs::new().family(Family::Monospace);
+    if modifier.contains(Modifier::BOLD) {
+        attrs = attrs.weight(Weight::BOLD);
+    }
+    if modifier.contains(Modifier::ITALIC) {
+        attrs = attrs.cache_key_flags(CacheKeyFlags::FAKE_ITALIC);
+    }
+    attrs
+}
+
+/// Measures the cell size in logical pixels by rasterizing a full block glyph.
+/// `metrics` are expected to be scaled by `scale_factor` already.
+fn measure_cell(
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    metrics: Metrics,
+    scale_factor: f32,
+) -> (usize, usize) {
+    let mut buffer = CosmicBuffer::new(font_system, metrics);
+    let mut buffer = buffer.borrow_with(font_system);
+    buffer.set_text(
+        "█\n█",
+        &Attrs::new().family(Family::Monospace),
+        Shaping::Advanced,
+    );
+    buffer.shape_until_scroll(true);
+    let boop = buffer.layout_runs().next().unwrap();
+    let glyph = boop.glyphs.iter().next().unwrap();
+    if glyph.glyph_id == 0 {
+        // No font has a full block, fall back to the advance and line height of the font.
+        return (
+            ((glyph.w / scale_factor).round() as usize).max(1),
+            ((metrics.line_height / scale_factor).ceil() as usize).max(1),
+        );
+    }
+    let physical_glyph = glyph.physical((0., 0.), 1.0);
+
+    let wa = swash_cache
+        .get_image(font_system, physical_glyph.cache_key)
+        .clone()
+        .unwrap()
+        .placement;
+
+    let physical_width = (wa.width as f32 * 0.9) as usize; // Reduce horizontal spacing by 10%
+    let physical_height = (wa.height as f32 * 0.85) as usize; // Reduce vertical spacing by 15%
+    (
+        ((physical_width as f32 / scale_factor).round() as usize).max(1),
+        ((physical_height as f32 / scale_factor).round() as usize).max(1),
+    )
+}
+
+impl CosmicRenderer {
+    /// Creates a renderer drawing with the fonts of the given font system.
+    pub fn new(mut font_system: FontSystem) -> Self {
+        let cosmic_buffer = CosmicBuffer::new(&mut font_system, Metrics::new(16.0, 16.0));
+        Self {
+            font_system,
+            cosmic_buffer,
+            swash_cache: SwashCache::new(),
+            subpixel_cache: SubpixelCache::new(),
+            ascii_faces: HashMap::new(),
+            ascii_glyphs: HashMap::new(),
+            ascii_fast_path: true,
+        }
+    }
+
+    /// Creates a renderer drawing with a single font, e.g. included with `include_bytes!`.
+    pub fn from_font_data(font_data: &[u8]) -> Self {
+        let mut db = Database::new();
+        db.load_font_data(font_data.to_vec());
+        // Cells are drawn with the monospace family, which has to resolve to the only font available.
+        let family = db.faces().next().and_then(|face| face.families.first()).map(|(name, _)| name.clone());
+        if let Some(family) = family {
+            db.set_monospace_family(family);
+        }
+        Self::new(FontSystem::new_with_locale_and_db("English".to_string(), db))
+    }
+
+    /// Creates a renderer drawing with the fonts installed on the system.
+    ///
+    /// ⚠️ Not supported on WASM/Web targets.
+    pub fn with_system_fonts() -> Self {
+        Self::new(FontSystem::new())
+    }
+
+    /// Sets whether single ASCII characters are looked up in the charmap of the primary font instead of being shaped,
+    /// which is on by default and much faster. Turn it off for fonts whose default OpenType features
+    /// replace single glyphs.
+    pub fn set_ascii_fast_path(&mut self, enabled: bool) {
+        self.ascii_fast_path = enabled;
+    }
+
+    /// Positions the glyphs of a cell, with y measured from the top of the cell.
+    /// Single ASCII characters skip shaping, everything else goes through cosmic-text.
+    fn cell_glyphs(&mut self, text: &CellText) -> Vec<PlacedGlyph> {
+        let mut chars = text.symbol.chars();
+        if let (Some(c), None) = (chars.next(), chars.next())
+            && self.ascii_fast_path
+            && (c.is_ascii_graphic() || c == ' ')
+            && let Some(glyph) = self.ascii_glyph(c, text.modifier)
+        {
+            return vec![glyph];
+        }
+
+        let mets = self.cosmic_buffer.metrics().font_size;
+        let line = self.cosmic_buffer.lines.get_mut(0).unwrap();
+        line.set_text(text.symbol, LineEnding::None, AttrsList::new(&cell_attrs(text.modifier)));
+        line.layout(&mut self.font_system, mets, None, Wrap::None, None, 1);
+
+        let mut glyphs = Vec::new();
+        for run in self.cosmic_buffer.layout_runs() {
+            for glyph in run.glyphs.iter() {
+                // Metrics are already scaled, so the layout is in physical pixels.
+                let physical_glyph = glyph.physical((0., 0.), 1.0);
+                glyphs.push(PlacedGlyph {
+                    cache_key: physical_glyph.cache_key,
+                    x: physical_glyph.x,
+                    y: run.line_y as i32 + physical_glyph.y,
+                });
+            }
+        }
+        glyphs
+    }
+
+    /// Looks up an ASCII character directly in the charmap of the primary font.
+    /// Returns `None` when the font lacks the glyph, so that shaping can pick a fallback font.
+    fn ascii_glyph(&mut self, c: char, modifier: Modifier) -> Option<PlacedGlyph> {
+        if let Some(glyph) = self.ascii_glyphs.get(&(c, modifier)) {
+            return *glyph;
+        }
+        let face = self.ascii_face(modifier);
+        let glyph = face.and_then(|face| {
+            let font = self.font_system.get_font(face.font_id)?;
+            let glyph_id = font.rustybuzz().glyph_index(c)?.0;
+            let font_size = self.cosmic_buffer.metrics().font_size;
+            let (cache_key, x, y) =
+                CacheKey::new(face.font_id, glyph_id, font_size, (0., 0.), face.flags);
+            Some(PlacedGlyph {
+                cache_key,
+                x,
+                y: y + face.line_y as i32,
+            })
+        });
+        self.ascii_glyphs.insert((c, modifier), glyph);
+        glyph
+    }
+
+    /// Finds the primary font and baseline for a style by shaping a reference character once.
+    fn ascii_face(&mut self, modifier: Modifier) -> Option<AsciiFace> {
+        if let Some(face) = self.ascii_faces.get(&modifier) {
+            return *face;
+        }
+        let mets = self.cosmic_buffer.metrics().font_size;
+        let line = self.cosmic_buffer.lines.get_mut(0).unwrap();
+        line.set_text("M", LineEnding::None, AttrsList::new(&cell_attrs(modifier)));
+        line.layout(&mut self.font_system, mets, None, Wrap::None, None, 1);
+
+        let face = self.cosmic_buffer.layout_runs().next().and_then(|run| {
+            run.glyphs.first().map(|glyph| AsciiFace {
+                font_id: glyph.font_id,
+                line_y: run.line_y,
+                flags: glyph.cache_key_flags,
+            })
+        });
+        self.ascii_faces.insert(modifier, face);
+        face
+    }
+}
+
+impl GlyphRenderer for CosmicRenderer {
+    fn set_size(&mut self, font_size: f32, scale_factor: f32) -> (usize, usize) {
+        let scaled_font_size = font_size * scale_factor;
+        let metrics = Metrics::new(scaled_font_size, scaled_font_size);
+        // Cached glyph images were rasterized at the old size and will never be hit again.
+        self.swash_cache = SwashCache::new();
+        self.subpixel_cache.clear();
+        self.ascii_faces.clear();
+        self.ascii_glyphs.clear();
+        self.cosmic_buffer.set_metrics(&mut self.font_system, metrics);
+
+        let (char_width, char_height) = measure_cell(
+            &mut self.font_system,
+            &mut self.swash_cache,
+            metrics,
+            scale_factor,
+        );
+        self.cosmic_buffer.set_size(
+            &mut self.font_system,
+            Some(char_width as f32 * scale_factor),
+            Some(char_height as f32 * scale_factor),
+        );
+        (
+            (char_width as f32 * scale_factor) as usize,
+            (char_height as f32 * scale_factor) as usize,
+        )
+    }
+
+    fn render(&mut self, text: &CellText) -> Vec<GlyphImage> {
+        let mut images = Vec::new();
+        for physical_glyph in self.cell_glyphs(text) {
+            let image = match text.antialiasing {
+                Antialiasing::Grayscale | Antialiasing::None => self
+                    .swash_cache
+                    .get_image(&mut self.font_system, physical_glyph.cache_key),
+                Antialiasing::Subpixel(order) => self.subpixel_cache.get_image(
+                    &mut self.font_system,
+                    physical_glyph.cache_key,
+                    order,
+                ),
+            };
+            if let Some(image) = image {
+                images.push(GlyphImage {
+                    left: physical_glyph.x + image.placement.left,
+                    top: physical_glyph.y - image.placement.top,
+                    width: image.placement.width as usize,
+                    height: image.placement.height as usize,
+                    content: match image.content {
+                        SwashContent::Mask => GlyphContent::Mask,
+                        SwashContent::SubpixelMask => GlyphContent::SubpixelMask,
+                        SwashContent::Color => GlyphContent::Color,
+                    },
+                    data: image.data.clone(),
+                });
+            }
+        }
+        images
+    }
+
+    /// Forks share the loaded fonts, but shape and rasterize with caches of their own.
+    fn fork(&self) -> Option<Box<dyn GlyphRenderer>> {
+        let locale = self.font_system.locale().to_string();
+        let mut fork = Self::new(FontSystem::new_with_locale_and_db(locale, self.font_system.db().clone()));
+        fork.ascii_fast_path = self.ascii_fast_path;
+        Some(Box::new(fork))
+    }
+}
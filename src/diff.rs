@@ -0,0 +1,193 @@
+//! Comparison of rendered pixmaps, e.g. for rendering regression tests.
+
+use alloc::vec::Vec;
+
+use crate::colors::luminance;
+use crate::pixmap::{PixelRect, RgbPixmap};
+
+/// Side of the square windows SSIM is computed over.
+const SSIM_WINDOW: usize = 8;
+
+/// The differences between two pixmaps, created with [`RgbPixmap::diff`].
+///
+/// The difference of a pixel is the largest difference of its color channels.
+/// Pixmaps of different size are compared over the larger size, pixels missing from either count as fully different.
+///
+/// # Examples
+/// ```rust
+/// # use soft_ratatui::{PixelRect, RgbPixmap};
+/// let expected = RgbPixmap::new(16, 16);
+/// let mut actual = expected.clone();
+/// actual.fill_rect(4, 2, 3, 2, [0, 0, 8]);
+///
+/// let diff = expected.diff(&actual);
+/// assert_eq!(diff.max_delta(), 8);
+/// assert_eq!(diff.count_over(2), 6);
+/// assert_eq!(diff.count_over(8), 0);
+/// assert_eq!(diff.bounds(2), Some(PixelRect { x: 4, y: 2, width: 3, height: 2 }));
+/// // A barely visible change keeps the structure intact.
+/// assert!(diff.ssim() > 0.9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PixmapDiff<'a> {
+    expected: &'a RgbPixmap,
+    actual: &'a RgbPixmap,
+    width: usize,
+    height: usize,
+    deltas: Vec<u8>,
+}
+
+impl RgbPixmap {
+    /// Compares the pixmap with another one, `self` being the expected and `other` the actual image.
+    pub fn diff<'a>(&'a self, other: &'a RgbPixmap) -> PixmapDiff<'a> {
+        let width = self.width().max(other.width());
+        let height = self.height().max(other.height());
+        let mut deltas = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let delta = match (pixel_at(self, x, y), pixel_at(other, x, y)) {
+                    (Some(e), Some(a)) => e.iter().zip(a).map(|(e, a)| e.abs_diff(a)).max().unwrap_or(0),
+                    _ => 255,
+                };
+                deltas.push(delta);
+            }
+        }
+        PixmapDiff {
+            expected: self,
+            actual: other,
+            width,
+            height,
+            deltas,
+        }
+    }
+}
+
+fn pixel_at(pixmap: &RgbPixmap, x: usize, y: usize) -> Option<[u8; 3]> {
+    (x < pixmap.width() && y < pixmap.height()).then(|| pixmap.get_pixel(x, y))
+}
+
+impl PixmapDiff<'_> {
+    /// Returns the width of the compared area in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the compared area in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns true when both pixmaps have the same size.
+    pub fn same_size(&self) -> bool {
+        (self.expected.width(), self.expected.height()) == (self.actual.width(), self.actual.height())
+    }
+
+    /// Returns the largest difference of any pixel, 0 for identical pixmaps.
+    pub fn max_delta(&self) -> u8 {
+        self.deltas.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns the number of pixels differing by more than `threshold`.
+    pub fn count_over(&self, threshold: u8) -> usize {
+        self.deltas.iter().filter(|&&delta| delta > threshold).count()
+    }
+
+    /// Returns the smallest rectangle containing all pixels differing by more than `threshold`.
+    pub fn bounds(&self, threshold: u8) -> Option<PixelRect> {
+        let width = self.width.max(1);
+        self.deltas
+            .iter()
+            .enumerate()
+            .filter(|(_, delta)| **delta > threshold)
+            .map(|(index, _)| PixelRect {
+                x: index % width,
+                y: index / width,
+                width: 1,
+                height: 1,
+            })
+            .reduce(PixelRect::union)
+    }
+
+    /// Returns the structural similarity (SSIM) of the luminance of both pixmaps, 1.0 for identical images.
+    /// Unlike counting pixels, SSIM barely reacts to slightly shifted antialiasing, but drops when shapes change,
+    /// so it tolerates rasterizer updates. It is computed over 8x8 windows and the least similar window is returned,
+    /// as averaging would hide a single changed character. Pixmaps of different size have a similarity of 0.0.
+    pub fn ssim(&self) -> f32 {
+        if !self.same_size() {
+            return 0.0;
+        }
+        // Stabilizing constants of the SSIM paper for 8 bit values.
+        const C1: f32 = (0.01 * 255.0) * (0.01 * 255.0);
+        const C2: f32 = (0.03 * 255.0) * (0.03 * 255.0);
+
+        let expected = luminances(self.expected);
+        let actual = luminances(self.actual);
+        let window_width = SSIM_WINDOW.min(self.width);
+        let window_height = SSIM_WINDOW.min(self.height);
+        if window_width == 0 || window_height == 0 {
+            return 1.0;
+        }
+        let count = (window_width * window_height) as f32;
+
+        let mut lowest: f32 = 1.0;
+        // Windows overlap by half, the last row and column of windows are aligned to the edges.
+        for top in window_starts(self.height, window_height) {
+            for left in window_starts(self.width, window_width) {
+                let (mut sum_e, mut sum_a, mut sum_ee, mut sum_aa, mut sum_ea) = (0.0, 0.0, 0.0, 0.0, 0.0);
+                for y in top..top + window_height {
+                    for x in left..left + window_width {
+                        let e = expected[y * self.width + x] as f32;
+                        let a = actual[y * self.width + x] as f32;
+                        sum_e += e;
+                        sum_a += a;
+                        sum_ee += e * e;
+                        sum_aa += a * a;
+                        sum_ea += e * a;
+                    }
+                }
+                let (mean_e, mean_a) = (sum_e / count, sum_a / count);
+                let var_e = sum_ee / count - mean_e * mean_e;
+                let var_a = sum_aa / count - mean_a * mean_a;
+                let covariance = sum_ea / count - mean_e * mean_a;
+                let ssim = ((2.0 * mean_e * mean_a + C1) * (2.0 * covariance + C2))
+                    / ((mean_e * mean_e + mean_a * mean_a + C1) * (var_e + var_a + C2));
+                lowest = lowest.min(ssim);
+            }
+        }
+        lowest
+    }
+
+    /// Draws the pixels differing by more than `threshold` in red over a faded grayscale copy of the actual pixmap,
+    /// so changed regions stand out.
+    pub fn to_image(&self, threshold: u8) -> RgbPixmap {
+        let mut image = RgbPixmap::new(self.width, self.height);
+        for (index, &delta) in self.deltas.iter().enumerate() {
+            let (x, y) = (index % self.width, index / self.width);
+            let color = if delta > threshold {
+                [255, 0, 0]
+            } else {
+                let faded = 64 + pixel_at(self.actual, x, y).map_or(0, luminance) / 4;
+                [faded, faded, faded]
+            };
+            image.put_pixel(x, y, color);
+        }
+        image
+    }
+}
+
+fn luminances(pixmap: &RgbPixmap) -> Vec<u8> {
+    pixmap
+        .data()
+        .chunks_exact(3)
+        .map(|pixel| luminance([pixel[0], pixel[1], pixel[2]]))
+        .collect()
+}
+
+/// Returns the starts of windows of `window` pixels stepping by half a window over `size` pixels,
+/// with the last window ending exactly at `size`.
+fn window_starts(size: usize, window: usize) -> impl Iterator<Item = usize> {
+    let last = size - window;
+    (0..last)
+        .step_by((window / 2).max(1))
+        .chain(core::iter::once(last))
+}
@@ -0,0 +1,202 @@
+//! Reduction of rendered pixmaps to the few gray levels of e-paper displays.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::colors::luminance;
+use crate::pixmap::{PixelRect, RgbPixmap};
+
+/// Number of gray levels of a [`GrayPixmap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrayDepth {
+    /// 1 bit per pixel, black and white.
+    Mono,
+    /// 2 bits per pixel, black, dark gray, light gray and white.
+    Gray4,
+}
+
+impl GrayDepth {
+    /// Returns the number of gray levels.
+    pub fn levels(self) -> u8 {
+        match self {
+            GrayDepth::Mono => 2,
+            GrayDepth::Gray4 => 4,
+        }
+    }
+
+    /// Returns the number of bits per pixel when packed.
+    pub fn bits(self) -> usize {
+        match self {
+            GrayDepth::Mono => 1,
+            GrayDepth::Gray4 => 2,
+        }
+    }
+}
+
+/// How brightness between two gray levels is approximated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Dithering {
+    /// Rounds every pixel to the nearest level. Keeps text sharpest, but flattens gradients.
+    #[default]
+    Threshold,
+    /// Floyd–Steinberg error diffusion, the smoothest result for images and gradients.
+    FloydSteinberg,
+    /// Ordered dithering with a 4x4 Bayer matrix. Stable between frames, so unchanged areas
+    /// dither the same way and partial refreshes show no seams.
+    Bayer,
+}
+
+/// 4x4 Bayer threshold matrix, values `0..16`.
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// A pixmap holding one gray level per pixel, 0 being black and `levels - 1` white.
+///
+/// # Examples
+/// ```rust
+/// # use soft_ratatui::{Dithering, GrayDepth, RgbPixmap};
+/// let mut pixmap = RgbPixmap::new(16, 2);
+/// pixmap.fill([128, 128, 128]);
+/// let gray = pixmap.dither(GrayDepth::Mono, Dithering::Bayer);
+/// // Half of the pixels are white.
+/// assert_eq!(gray.data().iter().filter(|&&level| level == 1).count(), 16);
+/// // Two bytes per row, most significant bit first, set bits are white.
+/// assert_eq!(gray.pack().len(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrayPixmap {
+    width: usize,
+    height: usize,
+    depth: GrayDepth,
+    data: Vec<u8>,
+}
+
+impl GrayPixmap {
+    /// Returns the width of the pixmap in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the pixmap in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the number of gray levels.
+    pub fn depth(&self) -> GrayDepth {
+        self.depth
+    }
+
+    /// Returns the gray levels of all pixels row by row, one byte per pixel.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the gray level of a pixel at (x, y).
+    pub fn get_level(&self, x: usize, y: usize) -> u8 {
+        debug_assert!(
+            x < self.width && y < self.height,
+            "Pixel coordinates out of bounds"
+        );
+        self.data[y * self.width + x]
+    }
+
+    /// Packs the whole pixmap the way e-paper controllers expect it, see [`GrayPixmap::pack_rect`].
+    pub fn pack(&self) -> Vec<u8> {
+        self.pack_rect(PixelRect {
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        })
+    }
+
+    /// Packs a rectangle of the pixmap for a partial refresh, clipped to the pixmap bounds.
+    /// Pixels are stored most significant bits first with every row padded to whole bytes,
+    /// higher values are brighter. Displays expecting set bits for black need the bytes inverted.
+    pub fn pack_rect(&self, rect: PixelRect) -> Vec<u8> {
+        let bits = self.depth.bits();
+        let x_end = (rect.x + rect.width).min(self.width);
+        let y_end = (rect.y + rect.height).min(self.height);
+        let columns = x_end.saturating_sub(rect.x);
+        let row_bytes = (columns * bits).div_ceil(8);
+
+        let mut packed = vec![0; row_bytes * y_end.saturating_sub(rect.y)];
+        for (row, out) in (rect.y..y_end).zip(packed.chunks_exact_mut(row_bytes.max(1))) {
+            let levels = &self.data[row * self.width + rect.x..row * self.width + x_end];
+            for (column, &level) in levels.iter().enumerate() {
+                let bit = column * bits;
+                out[bit / 8] |= level << (8 - bits - bit % 8);
+            }
+        }
+        packed
+    }
+
+    /// Converts the gray levels back to rgb, e.g. to preview the e-paper output on a desktop.
+    pub fn to_rgb(&self) -> RgbPixmap {
+        let max = (self.depth.levels() - 1) as u32;
+        let mut pixmap = RgbPixmap::new(self.width, self.height);
+        for (pixel, &level) in pixmap.data_mut().chunks_exact_mut(3).zip(&self.data) {
+            let gray = (level as u32 * 255 / max) as u8;
+            pixel.copy_from_slice(&[gray, gray, gray]);
+        }
+        pixmap
+    }
+}
+
+impl RgbPixmap {
+    /// Reduces the pixmap to black and white or four gray levels for e-paper displays.
+    /// Colors are converted by their luminance first, see [`SoftBackend::set_monochrome`](crate::SoftBackend::set_monochrome)
+    /// to keep colored text readable.
+    pub fn dither(&self, depth: GrayDepth, dithering: Dithering) -> GrayPixmap {
+        let max = (depth.levels() - 1) as i32;
+        // Brightness difference between two neighbouring levels.
+        let step = 255 / max;
+        let quantize = |value: i32| ((value * max + 127) / 255).clamp(0, max);
+
+        let mut data = Vec::with_capacity(self.width() * self.height());
+        match dithering {
+            Dithering::Threshold => {
+                for pixel in self.data().chunks_exact(3) {
+                    data.push(quantize(luminance([pixel[0], pixel[1], pixel[2]]) as i32) as u8);
+                }
+            }
+            Dithering::Bayer => {
+                for (index, pixel) in self.data().chunks_exact(3).enumerate() {
+                    let (x, y) = (index % self.width(), index / self.width());
+                    let threshold = BAYER_4X4[y % 4][x % 4] as i32;
+                    // Offsets the pixel by -0.5..0.5 of a level step.
+                    let offset = step * (2 * threshold + 1 - 16) / 32;
+                    let value = luminance([pixel[0], pixel[1], pixel[2]]) as i32 + offset;
+                    data.push(quantize(value) as u8);
+                }
+            }
+            Dithering::FloydSteinberg => {
+                // Errors carried into the current and the next row, with a pixel of padding on both sides.
+                let mut errors = vec![0i32; self.width() + 2];
+                let mut next_errors = vec![0i32; self.width() + 2];
+                for row in self.data().chunks_exact(3 * self.width().max(1)) {
+                    for (x, pixel) in row.chunks_exact(3).enumerate() {
+                        let value = luminance([pixel[0], pixel[1], pixel[2]]) as i32 + errors[x + 1] / 16;
+                        let level = quantize(value);
+                        data.push(level as u8);
+
+                        let error = value - level * 255 / max;
+                        errors[x + 2] += error * 7;
+                        next_errors[x] += error * 3;
+                        next_errors[x + 1] += error * 5;
+                        next_errors[x + 2] += error;
+                    }
+                    core::mem::swap(&mut errors, &mut next_errors);
+                    next_errors.fill(0);
+                }
+            }
+        }
+
+        GrayPixmap {
+            width: self.width(),
+            height: self.height(),
+            depth,
+            data,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Integration with [`embedded-graphics`](https://docs.rs/embedded-graphics), enabled by the `embedded-graphics` feature.
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::Pixel;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{Dimensions, OriginDimensions, Point, Size};
+use embedded_graphics_core::pixelcolor::{Rgb888, RgbColor};
+use embedded_graphics_core::primitives::Rectangle;
+
+use crate::pixmap::RgbPixmap;
+use crate::soft_backend::SoftBackend;
+
+impl OriginDimensions for RgbPixmap {
+    fn size(&self) -> Size {
+        Size::new(self.width() as u32, self.height() as u32)
+    }
+}
+
+/// Lets embedded-graphics primitives, fonts and images be drawn into a pixmap.
+impl DrawTarget for RgbPixmap {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (width, height) = (self.width() as i32, self.height() as i32);
+        for Pixel(point, color) in pixels {
+            if (0..width).contains(&point.x) && (0..height).contains(&point.y) {
+                self.put_pixel(point.x as usize, point.y as usize, [color.r(), color.g(), color.b()]);
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        if let Some(bottom_right) = area.bottom_right() {
+            self.fill_rect(
+                area.top_left.x as usize,
+                area.top_left.y as usize,
+                (bottom_right.x - area.top_left.x + 1) as usize,
+                (bottom_right.y - area.top_left.y + 1) as usize,
+                [color.r(), color.g(), color.b()],
+            );
+        }
+        Ok(())
+    }
+}
+
+impl RgbPixmap {
+    /// Draws the whole pixmap onto an embedded-graphics display with its top-left corner at `origin`.
+    /// Colors are converted to the color type of the display, e.g. `Rgb565` for SPI LCDs
+    /// or `BinaryColor` for e-paper, where pixels at or above half luminance become `On`.
+    pub fn draw_to_target<D>(&self, target: &mut D, origin: Point) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        D::Color: From<Rgb888>,
+    {
+        let area = Rectangle::new(origin, OriginDimensions::size(self));
+        let colors = self
+            .data()
+            .chunks_exact(3)
+            .map(|pixel| Rgb888::new(pixel[0], pixel[1], pixel[2]).into());
+        target.fill_contiguous(&area, colors)
+    }
+}
+
+impl SoftBackend {
+    /// Draws the rendered terminal onto an embedded-graphics display, see [`RgbPixmap::draw_to_target`].
+    /// Works the same with the embedded-graphics simulator, so UIs for small displays can be tested on a desktop.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use embedded_graphics_core::geometry::Point;
+    /// # use soft_ratatui::{RgbPixmap, SoftBackend, Tileset};
+    /// # let tileset = Tileset::from_pixmap(&RgbPixmap::new(128, 128), 8, 8, [255, 0, 255]).unwrap();
+    /// let backend = SoftBackend::new_with_tileset(16, 8, tileset, 1.0);
+    /// // Any DrawTarget works, here another pixmap stands in for the display.
+    /// let mut display = RgbPixmap::new(160, 80);
+    /// backend.draw_to_target(&mut display, Point::new(16, 8)).unwrap();
+    /// ```
+    pub fn draw_to_target<D>(&self, target: &mut D, origin: Point) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        D::Color: From<Rgb888>,
+    {
+        self.rgb_pixmap.draw_to_target(target, origin)
+    }
+}
@@ -0,0 +1,77 @@
+//! Splits cell symbols into printable text and the escape sequences apps hide in them.
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Unicode placeholder of the Kitty graphics protocol, the cells of a virtual image placement are filled with it.
+pub(crate) const KITTY_PLACEHOLDER: char = '\u{10EEEE}';
+
+/// A part of a cell symbol.
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    /// Operating system command (`ESC ]`), e.g. OSC 8 hyperlinks.
+    Osc(&'a str),
+    /// Device control string (`ESC P`), e.g. Sixel images.
+    Dcs(&'a str),
+    /// Application program command (`ESC _`), e.g. Kitty graphics.
+    Apc(&'a str),
+    /// Any other escape sequence, carries nothing we draw.
+    Other,
+}
+
+/// Returns whether a symbol needs [`tokens`] to be drawn, plain symbols are drawn as they are.
+pub(crate) fn has_escapes(symbol: &str) -> bool {
+    symbol.contains([ESC, KITTY_PLACEHOLDER])
+}
+
+/// Splits a symbol into text and escape sequences.
+pub(crate) fn tokens(symbol: &str) -> impl Iterator<Item = Token<'_>> {
+    let mut rest = symbol;
+    core::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let Some(after_esc) = rest.strip_prefix(ESC) else {
+            let end = rest.find(ESC).unwrap_or(rest.len());
+            let (text, next) = rest.split_at(end);
+            rest = next;
+            return Some(Token::Text(text));
+        };
+        let mut chars = after_esc.chars();
+        let token = match chars.next() {
+            Some(introducer @ (']' | 'P' | '_' | '^' | 'X')) => {
+                let (payload, next) = split_string(chars.as_str());
+                rest = next;
+                match introducer {
+                    ']' => Token::Osc(payload),
+                    'P' => Token::Dcs(payload),
+                    '_' => Token::Apc(payload),
+                    _ => Token::Other,
+                }
+            }
+            Some('[') => {
+                let csi = chars.as_str();
+                let end = csi.find(|c| ('\x40'..='\x7e').contains(&c)).map_or(csi.len(), |end| end + 1);
+                rest = &csi[end..];
+                Token::Other
+            }
+            _ => {
+                rest = chars.as_str();
+                Token::Other
+            }
+        };
+        Some(token)
+    })
+}
+
+/// Splits the payload of a string sequence from the rest, it ends with BEL or the string terminator `ESC \`.
+fn split_string(sequence: &str) -> (&str, &str) {
+    match sequence.find([BEL, ESC]) {
+        Some(end) if sequence[end..].starts_with(BEL) => (&sequence[..end], &sequence[end + 1..]),
+        Some(end) => {
+            let next = &sequence[end + 1..];
+            (&sequence[..end], next.strip_prefix('\\').unwrap_or(next))
+        }
+        None => (sequence, ""),
+    }
+}
@@ -0,0 +1,113 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use ratatui_core::style::Modifier;
+
+use crate::subpixel::Antialiasing;
+
+/// Turns the text of a cell into glyph images, so that [`SoftBackend`](crate::SoftBackend) can use any glyph provider.
+///
+/// The backend takes care of caching rendered cells, painting backgrounds and blending the images,
+/// a renderer only has to rasterize. Implemented by the cosmic-text based [`CosmicRenderer`](crate::CosmicRenderer),
+/// [`BitmapFont`](crate::BitmapFont) and [`Tileset`](crate::Tileset).
+///
+/// # Examples
+/// A renderer drawing every non-blank cell as a solid block, handy for tests that need no font:
+/// ```rust
+/// # use soft_ratatui::{CellText, GlyphContent, GlyphImage, GlyphRenderer, SoftBackend};
+/// struct Blocks;
+///
+/// impl GlyphRenderer for Blocks {
+///     fn set_size(&mut self, _font_size: f32, scale_factor: f32) -> (usize, usize) {
+///         ((4.0 * scale_factor) as usize, (8.0 * scale_factor) as usize)
+///     }
+///
+///     fn render(&mut self, text: &CellText) -> Vec<GlyphImage> {
+///         if text.symbol.trim().is_empty() {
+///             return Vec::new();
+///         }
+///         vec![GlyphImage {
+///             left: 0,
+///             top: 0,
+///             width: 4,
+///             height: 8,
+///             content: GlyphContent::Mask,
+///             data: vec![255; 4 * 8],
+///         }]
+///     }
+/// }
+///
+/// let backend = SoftBackend::new_with_renderer(20, 10, 16.0, Blocks, 1.0);
+/// assert_eq!(backend.get_pixmap_width(), 20 * 4);
+/// ```
+pub trait GlyphRenderer: Send + Sync {
+    /// Prepares for a new font size in pixels and scale factor, returning the size of a cell in physical pixels.
+    /// Called on creation and whenever either value changes, caches depending on the size should be dropped here.
+    fn set_size(&mut self, font_size: f32, scale_factor: f32) -> (usize, usize);
+
+    /// Rasterizes the text of a cell into images positioned relative to the top-left corner of the cell.
+    /// Images may overflow into neighbouring cells, pixels above or left of the cell are dropped.
+    fn render(&mut self, text: &CellText) -> Vec<GlyphImage>;
+
+    /// Returns an independent copy producing the same images, so that cells can be rasterized on several threads
+    /// with the `rayon` feature. The backend sets the size of the copy itself.
+    /// Renderers returning `None`, the default, rasterize on a single thread.
+    fn fork(&self) -> Option<Box<dyn GlyphRenderer>> {
+        None
+    }
+}
+
+/// The text of a cell and everything that influences how it looks.
+#[derive(Debug, Clone, Copy)]
+pub struct CellText<'a> {
+    /// The symbol of the cell. Underlined and crossed out text carries a U+0332 or U+0336
+    /// combining mark after every character.
+    pub symbol: &'a str,
+    /// The style of the text, only [`Modifier::BOLD`] and [`Modifier::ITALIC`] are set.
+    pub modifier: Modifier,
+    /// Color of the text.
+    pub fg: [u8; 3],
+    /// Color of the cell background.
+    pub bg: [u8; 3],
+    /// Requested edge smoothing, masks are thresholded by the backend for [`Antialiasing::None`].
+    pub antialiasing: Antialiasing,
+}
+
+/// How the bytes of a [`GlyphImage`] are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlyphContent {
+    /// One coverage byte per pixel, blended from background to foreground color.
+    Mask,
+    /// Four bytes per pixel holding the coverage of the red, green and blue channel, the fourth byte is unused.
+    SubpixelMask,
+    /// RGBA pixels drawn as they are, blended over the background by their alpha.
+    Color,
+}
+
+impl GlyphContent {
+    /// Returns the number of bytes stored for every pixel.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            GlyphContent::Mask => 1,
+            GlyphContent::SubpixelMask | GlyphContent::Color => 4,
+        }
+    }
+}
+
+/// A rasterized glyph.
+#[derive(Debug, Clone)]
+pub struct GlyphImage {
+    /// Horizontal position of the left edge, relative to the cell.
+    pub left: i32,
+    /// Vertical position of the top edge, relative to the cell.
+    pub top: i32,
+    /// Width in pixels.
+    pub width: usize,
+    /// Height in pixels.
+    pub height: usize,
+    /// Format of `data`.
+    pub content: GlyphContent,
+    /// Pixels row by row, without padding, so exactly `width * height * content.bytes_per_pixel()` bytes.
+    /// Images of any other length are skipped.
+    pub data: Vec<u8>,
+}
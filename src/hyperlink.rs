@@ -0,0 +1,96 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use ratatui_core::buffer::{Buffer, CellWidth};
+
+use crate::escape::{KITTY_PLACEHOLDER, Token, has_escapes, tokens};
+use crate::screen_text::row_cells;
+
+/// A cell symbol with its escape sequences removed.
+pub(crate) struct EscapedSymbol<'a> {
+    /// Target of the OSC 8 hyperlink the text is wrapped in.
+    pub url: Option<&'a str>,
+    /// The printable text, terminals show it in the cell and the cells following it.
+    pub text: String,
+}
+
+/// Splits a symbol containing escape sequences into its printable text and OSC 8 hyperlink target,
+/// `None` for plain symbols. Other sequences and Kitty image placeholders are dropped.
+pub(crate) fn parse_escapes(symbol: &str) -> Option<EscapedSymbol<'_>> {
+    if !has_escapes(symbol) {
+        return None;
+    }
+    let mut url = None;
+    let mut text = String::new();
+    for token in tokens(symbol) {
+        match token {
+            Token::Text(part) => {
+                let mut placeholder = false;
+                for c in part.chars() {
+                    let mut buffer = [0; 4];
+                    // Placeholders are followed by combining diacritics encoding their row and column.
+                    if c == KITTY_PLACEHOLDER || (placeholder && c.encode_utf8(&mut buffer).cell_width() == 0) {
+                        placeholder = true;
+                        continue;
+                    }
+                    placeholder = false;
+                    text.push(c);
+                }
+            }
+            // OSC 8 ; params ; uri, an empty uri closes the link.
+            Token::Osc(payload) => {
+                if let Some(link) = payload.strip_prefix("8;")
+                    && let Some((_, target)) = link.split_once(';')
+                    && !target.is_empty()
+                {
+                    url = Some(target);
+                }
+            }
+            Token::Dcs(_) | Token::Apc(_) | Token::Other => {}
+        }
+    }
+    Some(EscapedSymbol { url, text })
+}
+
+/// Returns what a cell shows: the symbol itself, or for symbols with escape sequences
+/// only the first character of their text, the following ones belong to the next cells.
+pub(crate) fn display_symbol(symbol: &str) -> Cow<'_, str> {
+    let Some(escaped) = parse_escapes(symbol) else {
+        return Cow::Borrowed(symbol);
+    };
+    let mut first = String::new();
+    for (index, c) in escaped.text.chars().enumerate() {
+        let mut buffer = [0; 4];
+        // Keep combining marks following the first character.
+        if index > 0 && c.encode_utf8(&mut buffer).cell_width() > 0 {
+            break;
+        }
+        first.push(c);
+    }
+    Cow::Owned(first)
+}
+
+/// A hyperlink on a row, covering the columns `first..=last`.
+pub(crate) struct LinkSpan<'a> {
+    pub first: u16,
+    pub last: u16,
+    pub url: &'a str,
+}
+
+/// Returns the hyperlinks of a row from left to right. Neighbouring cells linking to the same target are merged,
+/// as apps split the text of one link across several cells.
+pub(crate) fn row_links(buffer: &Buffer, y: u16) -> Vec<LinkSpan<'_>> {
+    let mut links: Vec<LinkSpan<'_>> = Vec::new();
+    for (x, cell, _) in row_cells(buffer, y) {
+        let Some(EscapedSymbol { url: Some(url), text }) = parse_escapes(cell.symbol()) else {
+            continue;
+        };
+        let last = x.saturating_add(text.as_str().cell_width().max(1) - 1);
+        match links.last_mut() {
+            Some(link) if link.url == url && link.last.saturating_add(1) >= x => link.last = link.last.max(last),
+            _ => links.push(LinkSpan { first: x, last, url }),
+        }
+    }
+    links
+}
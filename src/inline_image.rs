@@ -0,0 +1,553 @@
+//! Decodes the Sixel and Kitty graphics protocols that image widgets write into cell symbols.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
+use crate::escape::{KITTY_PLACEHOLDER, Token, tokens};
+use crate::pixmap::RgbPixmap;
+
+/// Largest width or height of a decoded image, larger ones are cropped.
+const MAX_IMAGE_SIZE: usize = 8192;
+/// Largest number of pixels of a decoded image, 16 MiB as RGBA. Larger Sixel images are cropped,
+/// larger Kitty images are skipped.
+const MAX_IMAGE_PIXELS: usize = 2048 * 2048;
+/// Memory budget of the images stored for Kitty placements, room for two of the largest RGBA images.
+const MAX_STORED_IMAGE_BYTES: usize = 2 * 4 * MAX_IMAGE_PIXELS;
+/// Bookkeeping cost of a stored image on top of its pixels, so that tiny images are not free.
+const STORED_IMAGE_OVERHEAD_BYTES: usize = 64;
+
+/// An image with optional transparency, as placed over the cells of the grid.
+pub(crate) struct InlineImage {
+    pub pixels: RgbPixmap,
+    /// One opacity value per pixel, `None` for opaque images.
+    pub alpha: Option<Vec<u8>>,
+}
+
+impl InlineImage {
+    /// Memory used by the pixels and their opacity.
+    pub fn byte_size(&self) -> usize {
+        self.pixels.data().len() + self.alpha.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Returns the top left `visible` part of the image resized to the given size by nearest neighbour sampling,
+    /// so images scaled past the grid only allocate the pixels that are shown.
+    pub fn scaled(&self, width: usize, height: usize, visible: (usize, usize)) -> InlineImage {
+        let (source_width, source_height) = (self.pixels.width(), self.pixels.height());
+        let (visible_width, visible_height) = (visible.0.min(width), visible.1.min(height));
+        let mut pixels = RgbPixmap::new(visible_width, visible_height);
+        let mut alpha = self.alpha.as_ref().map(|_| vec![0; visible_width * visible_height]);
+        if source_width == 0 || source_height == 0 {
+            return InlineImage { pixels, alpha };
+        }
+        for y in 0..visible_height {
+            let source_y = y * source_height / height;
+            for x in 0..visible_width {
+                let source_x = x * source_width / width;
+                pixels.put_pixel(x, y, self.pixels.get_pixel(source_x, source_y));
+                if let (Some(alpha), Some(source)) = (alpha.as_mut(), self.alpha.as_ref()) {
+                    alpha[y * visible_width + x] = source[source_y * source_width + source_x];
+                }
+            }
+        }
+        InlineImage { pixels, alpha }
+    }
+}
+
+/// Images transmitted with the Kitty graphics protocol, by id, for later placements.
+/// Bounded by [`MAX_STORED_IMAGE_BYTES`], the oldest images without a placement are dropped first.
+#[derive(Default)]
+pub(crate) struct StoredImages {
+    /// Images by id, with the order they were stored in.
+    images: HashMap<u32, (u64, Arc<InlineImage>)>,
+    /// Ids by the order they were stored in, the oldest first.
+    order: BTreeMap<u64, u32>,
+    stored: u64,
+    used_bytes: usize,
+}
+
+impl StoredImages {
+    pub fn get(&self, id: u32) -> Option<&Arc<InlineImage>> {
+        self.images.get(&id).map(|(_, image)| image)
+    }
+
+    pub fn remove(&mut self, id: u32) -> Option<Arc<InlineImage>> {
+        let (order, image) = self.images.remove(&id)?;
+        self.order.remove(&order);
+        self.used_bytes -= image.byte_size() + STORED_IMAGE_OVERHEAD_BYTES;
+        Some(image)
+    }
+
+    pub fn clear(&mut self) {
+        self.images.clear();
+        self.order.clear();
+        self.used_bytes = 0;
+    }
+
+    /// Stores an image, replacing the image stored with the same id. While over the memory budget,
+    /// the oldest images `placed` returns false for are dropped, the new image is always kept.
+    pub fn insert(&mut self, id: u32, image: Arc<InlineImage>, placed: impl Fn(&Arc<InlineImage>) -> bool) {
+        self.remove(id);
+        self.stored += 1;
+        self.used_bytes += image.byte_size() + STORED_IMAGE_OVERHEAD_BYTES;
+        self.images.insert(id, (self.stored, image));
+        self.order.insert(self.stored, id);
+        while self.used_bytes > MAX_STORED_IMAGE_BYTES {
+            let oldest = self.order.range(..self.stored).map(|(_, &id)| id).find(|id| !placed(&self.images[id].1));
+            let Some(id) = oldest else {
+                break;
+            };
+            self.remove(id);
+        }
+    }
+}
+
+/// An image command found in a cell symbol.
+pub(crate) enum ImageCommand {
+    /// Shows an image at the cell holding the command. The size in cells stretches the image over them,
+    /// without it the image keeps its pixel size.
+    Display {
+        image: Arc<InlineImage>,
+        columns: Option<u16>,
+        rows: Option<u16>,
+        /// The cells are marked with Kitty unicode placeholders, which give the size when it is missing.
+        placeholders: bool,
+    },
+    /// Keeps a Kitty image for later [`ImageCommand::Put`] commands.
+    Store { id: u32, image: Arc<InlineImage> },
+    /// Shows a stored Kitty image at the cell holding the command.
+    Put {
+        id: u32,
+        columns: Option<u16>,
+        rows: Option<u16>,
+        placeholders: bool,
+    },
+    /// Removes Kitty images, all of them without an id.
+    Delete { id: Option<u32> },
+}
+
+/// Returns the image commands of a symbol. Sixel images, Kitty images in raw RGB or RGBA
+/// and Kitty placement and delete commands are understood, everything else is skipped.
+pub(crate) fn image_commands(symbol: &str) -> Vec<ImageCommand> {
+    let mut commands = Vec::new();
+    let mut kitty: Option<(KittyControl, String)> = None;
+    for token in tokens(symbol) {
+        match token {
+            Token::Dcs(payload) => {
+                if let Some(image) = decode_sixel(payload) {
+                    commands.push(ImageCommand::Display {
+                        image: Arc::new(image),
+                        columns: None,
+                        rows: None,
+                        placeholders: false,
+                    });
+                }
+            }
+            Token::Apc(payload) => {
+                let Some(payload) = payload.strip_prefix('G') else {
+                    continue;
+                };
+                let (control, data) = payload.split_once(';').unwrap_or((payload, ""));
+                let chunk = KittyControl::parse(control);
+                // Only the first chunk of a chunked transmission carries the keys, the following ones continue it.
+                let (control, mut data_so_far) = match kitty.take() {
+                    Some((control, data_so_far)) => (control, data_so_far),
+                    None => (chunk, String::new()),
+                };
+                data_so_far.push_str(data);
+                if chunk.more {
+                    kitty = Some((control, data_so_far));
+                } else {
+                    commands.extend(kitty_command(&control, &data_so_far));
+                }
+            }
+            Token::Text(_) | Token::Osc(_) | Token::Other => {}
+        }
+    }
+    commands
+}
+
+/// Returns whether a symbol holds Kitty unicode placeholders, marking cells covered by an image.
+pub(crate) fn has_placeholders(symbol: &str) -> bool {
+    symbol.contains(KITTY_PLACEHOLDER)
+}
+
+/// Counts the Kitty unicode placeholders of a symbol, one per covered column.
+pub(crate) fn placeholder_count(symbol: &str) -> usize {
+    symbol.matches(KITTY_PLACEHOLDER).count()
+}
+
+/// The control keys of a Kitty graphics command that matter for drawing.
+#[derive(Clone, Copy)]
+struct KittyControl {
+    action: char,
+    format: u32,
+    width: usize,
+    height: usize,
+    compressed: bool,
+    direct: bool,
+    more: bool,
+    id: Option<u32>,
+    columns: Option<u16>,
+    rows: Option<u16>,
+    placeholders: bool,
+    delete: char,
+}
+
+impl KittyControl {
+    fn parse(control: &str) -> Self {
+        let mut parsed = KittyControl {
+            action: 't',
+            format: 32,
+            width: 0,
+            height: 0,
+            compressed: false,
+            direct: true,
+            more: false,
+            id: None,
+            columns: None,
+            rows: None,
+            placeholders: false,
+            delete: 'a',
+        };
+        for pair in control.split(',') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let first = value.chars().next().unwrap_or_default();
+            match key {
+                "a" => parsed.action = first,
+                "f" => parsed.format = value.parse().unwrap_or(0),
+                "s" => parsed.width = value.parse().unwrap_or(0),
+                "v" => parsed.height = value.parse().unwrap_or(0),
+                "o" => parsed.compressed = !value.is_empty(),
+                "t" => parsed.direct = first == 'd',
+                "m" => parsed.more = value == "1",
+                "i" => parsed.id = value.parse().ok(),
+                "c" => parsed.columns = value.parse().ok().filter(|&columns| columns > 0),
+                "r" => parsed.rows = value.parse().ok().filter(|&rows| rows > 0),
+                "U" => parsed.placeholders = value == "1",
+                "d" => parsed.delete = first,
+                _ => {}
+            }
+        }
+        parsed
+    }
+}
+
+/// Turns a complete Kitty graphics command into image commands.
+fn kitty_command(control: &KittyControl, data: &str) -> Vec<ImageCommand> {
+    let mut commands = Vec::new();
+    match control.action {
+        't' | 'T' => {
+            let Some(image) = decode_kitty_pixels(control, data).map(Arc::new) else {
+                return commands;
+            };
+            if let Some(id) = control.id {
+                commands.push(ImageCommand::Store {
+                    id,
+                    image: image.clone(),
+                });
+            }
+            if control.action == 'T' {
+                commands.push(ImageCommand::Display {
+                    image,
+                    columns: control.columns,
+                    rows: control.rows,
+                    placeholders: control.placeholders,
+                });
+            }
+        }
+        'p' => {
+            if let Some(id) = control.id {
+                commands.push(ImageCommand::Put {
+                    id,
+                    columns: control.columns,
+                    rows: control.rows,
+                    placeholders: control.placeholders,
+                });
+            }
+        }
+        'd' => {
+            let id = if control.delete.eq_ignore_ascii_case(&'i') { control.id } else { None };
+            commands.push(ImageCommand::Delete { id });
+        }
+        _ => {}
+    }
+    commands
+}
+
+/// Decodes directly transmitted, uncompressed RGB (`f=24`) or RGBA (`f=32`) pixels.
+/// PNG data and compressed or file based transmissions are not supported.
+fn decode_kitty_pixels(control: &KittyControl, data: &str) -> Option<InlineImage> {
+    let channels = match control.format {
+        24 => 3,
+        32 => 4,
+        _ => return None,
+    };
+    if control.compressed || !control.direct {
+        return None;
+    }
+    let (width, height) = (control.width, control.height);
+    if width == 0 || height == 0 || width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
+        return None;
+    }
+    if width * height > MAX_IMAGE_PIXELS {
+        return None;
+    }
+    let bytes = decode_base64(data)?;
+    if bytes.len() < width * height * channels {
+        return None;
+    }
+    let mut pixels = RgbPixmap::new(width, height);
+    for (pixel, source) in pixels.data_mut().chunks_exact_mut(3).zip(bytes.chunks_exact(channels)) {
+        pixel.copy_from_slice(&source[..3]);
+    }
+    let alpha = (channels == 4).then(|| bytes.chunks_exact(4).take(width * height).map(|pixel| pixel[3]).collect());
+    Some(InlineImage { pixels, alpha })
+}
+
+/// Decodes standard base64, ignoring whitespace and padding.
+fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(data.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in data.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' | b' ' | b'\n' | b'\r' | b'\t' => continue,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// Default color registers of the VT340, later registers start out black.
+const SIXEL_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [51, 51, 204],
+    [204, 33, 33],
+    [51, 204, 51],
+    [204, 51, 204],
+    [51, 204, 204],
+    [204, 204, 51],
+    [135, 135, 135],
+    [66, 66, 66],
+    [84, 84, 153],
+    [153, 66, 66],
+    [84, 153, 84],
+    [153, 84, 153],
+    [84, 153, 153],
+    [153, 153, 84],
+    [204, 204, 204],
+];
+
+/// Decodes a Sixel image from the payload of a device control string (`P1;P2;P3 q data`).
+/// Pixels the image never sets stay transparent, so the cells below show through like the terminal background.
+fn decode_sixel(payload: &str) -> Option<InlineImage> {
+    let start = payload.find(|c: char| !c.is_ascii_digit() && c != ';')?;
+    let data = payload[start..].strip_prefix('q')?;
+
+    let mut palette = [[0u8; 3]; 256];
+    palette[..16].copy_from_slice(&SIXEL_PALETTE);
+    let mut canvas = SixelCanvas::default();
+    let (mut x, mut y, mut color) = (0usize, 0usize, 0usize);
+
+    let bytes = data.as_bytes();
+    let mut index = 0;
+    // Reads a list of numeric parameters separated by semicolons.
+    let read_numbers = |index: &mut usize| {
+        let mut numbers: Vec<usize> = Vec::new();
+        let mut current: Option<usize> = None;
+        while let Some(&byte) = bytes.get(*index) {
+            match byte {
+                b'0'..=b'9' => {
+                    current = Some(current.unwrap_or(0).saturating_mul(10).saturating_add((byte - b'0') as usize))
+                }
+                b';' => numbers.push(current.take().unwrap_or(0)),
+                _ => break,
+            }
+            *index += 1;
+        }
+        numbers.extend(current);
+        numbers
+    };
+
+    while let Some(&byte) = bytes.get(index) {
+        index += 1;
+        match byte {
+            b'?'..=b'~' | b'!' => {
+                let (count, sixel) = if byte == b'!' {
+                    let count = read_numbers(&mut index).first().copied().unwrap_or(1);
+                    let Some(&sixel @ b'?'..=b'~') = bytes.get(index) else {
+                        continue;
+                    };
+                    index += 1;
+                    (count.max(1), sixel)
+                } else {
+                    (1, byte)
+                };
+                let bits = sixel - b'?';
+                for _ in 0..count.min(MAX_IMAGE_SIZE) {
+                    canvas.set_sixel(x, y, bits, palette[color]);
+                    x += 1;
+                }
+            }
+            b'#' => {
+                let numbers = read_numbers(&mut index);
+                color = numbers.first().copied().unwrap_or(0).min(255);
+                if let [_, model, a, b, c, ..] = numbers[..] {
+                    palette[color] = match model {
+                        1 => hls_to_rgb(a, b, c),
+                        _ => [a, b, c].map(|percent| ((percent.min(100) * 255 + 50) / 100) as u8),
+                    };
+                }
+            }
+            b'"' => {
+                if let [_, _, width, height, ..] = read_numbers(&mut index)[..] {
+                    canvas.declare(width, height);
+                }
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                y += 6;
+            }
+            _ => {}
+        }
+    }
+    canvas.finish()
+}
+
+/// Converts a Sixel HLS color, with hue in degrees starting at blue and lightness and saturation in percent.
+fn hls_to_rgb(hue: usize, lightness: usize, saturation: usize) -> [u8; 3] {
+    let hue = ((hue + 240) % 360) as f32 / 360.0;
+    let lightness = lightness.min(100) as f32 / 100.0;
+    let saturation = saturation.min(100) as f32 / 100.0;
+    let q = if lightness < 0.5 {
+        lightness * (1.0 + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2.0 * lightness - q;
+    let channel = |t: f32| {
+        let t = (t + 1.0) % 1.0;
+        let value = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        (value * 255.0 + 0.5) as u8
+    };
+    [channel(hue + 1.0 / 3.0), channel(hue), channel(hue - 1.0 / 3.0)]
+}
+
+/// A growing image that Sixel data is painted into.
+#[derive(Default)]
+struct SixelCanvas {
+    width: usize,
+    height: usize,
+    /// Size from the raster attributes, the image is at least this large.
+    declared: (usize, usize),
+    pixels: Vec<[u8; 4]>,
+}
+
+impl SixelCanvas {
+    fn declare(&mut self, width: usize, height: usize) {
+        let width = width.min(MAX_IMAGE_SIZE);
+        let height = height.min(MAX_IMAGE_SIZE).min(MAX_IMAGE_PIXELS / width.max(1));
+        self.declared = (width, height);
+        self.grow(self.declared.0, self.declared.1);
+    }
+
+    fn grow(&mut self, width: usize, height: usize) {
+        if width <= self.width && height <= self.height {
+            return;
+        }
+        let (new_width, new_height) = (width.max(self.width), height.max(self.height));
+        let mut pixels = vec![[0; 4]; new_width * new_height];
+        for y in 0..self.height {
+            pixels[y * new_width..y * new_width + self.width]
+                .copy_from_slice(&self.pixels[y * self.width..(y + 1) * self.width]);
+        }
+        (self.width, self.height, self.pixels) = (new_width, new_height, pixels);
+    }
+
+    /// Paints the pixels of one sixel, a column of six pixels with the top one in the lowest bit.
+    fn set_sixel(&mut self, x: usize, y: usize, bits: u8, color: [u8; 3]) {
+        if bits == 0 || x >= MAX_IMAGE_SIZE || y >= MAX_IMAGE_SIZE {
+            return;
+        }
+        let rows = ((8 - bits.leading_zeros()) as usize).min(MAX_IMAGE_SIZE - y);
+        let needed = ((x + 1).max(self.width), (y + rows).max(self.height));
+        if needed.0 * needed.1 > MAX_IMAGE_PIXELS {
+            return;
+        }
+        // Grow by doubling, so images without raster attributes do not reallocate for every column.
+        // The doubled size stays within the pixel limit, which the needed size already fits.
+        let width = if x < self.width {
+            self.width
+        } else {
+            needed.0.max(self.width * 2).min(MAX_IMAGE_SIZE).min(MAX_IMAGE_PIXELS / needed.1)
+        };
+        let height = if y + rows <= self.height {
+            self.height
+        } else {
+            needed.1.max(self.height * 2).min(MAX_IMAGE_SIZE).min(MAX_IMAGE_PIXELS / width)
+        };
+        self.grow(width, height);
+        for row in 0..rows {
+            if bits & (1 << row) != 0 {
+                let [r, g, b] = color;
+                self.pixels[(y + row) * self.width + x] = [r, g, b, 255];
+            }
+        }
+    }
+
+    /// Crops the canvas to the painted pixels, or the declared size if larger.
+    fn finish(self) -> Option<InlineImage> {
+        let mut used = self.declared;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.pixels[y * self.width + x][3] != 0 {
+                    used = (used.0.max(x + 1), used.1.max(y + 1));
+                }
+            }
+        }
+        let (width, height) = used;
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let mut pixels = RgbPixmap::new(width, height);
+        let mut alpha = vec![0; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let [r, g, b, a] = self.pixels[y * self.width + x];
+                pixels.put_pixel(x, y, [r, g, b]);
+                alpha[y * width + x] = a;
+            }
+        }
+        let opaque = alpha.iter().all(|&a| a == 255);
+        Some(InlineImage {
+            pixels,
+            alpha: (!opaque).then_some(alpha),
+        })
+    }
+}
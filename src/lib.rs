@@ -1,7 +1,55 @@
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("soft_ratatui needs the `std` feature, or the `alloc` feature for builds without `std`");
+#[cfg(not(target_has_atomic = "ptr"))]
+compile_error!("soft_ratatui shares rendered cells through `Arc` and needs a target with pointer sized atomics");
+
 mod soft_backend;
 
-pub use pixmap::RgbPixmap;
-pub use soft_backend::SoftBackend;
+pub use bitmap_font::{BitmapFont, BitmapFontError};
+pub use blink::BlinkTiming;
+pub use colors::TextBlending;
+#[cfg(feature = "bundled-font")]
+pub use cosmic_renderer::BUNDLED_FONT;
+#[cfg(feature = "cosmic")]
+pub use cosmic_renderer::CosmicRenderer;
+pub use cp437::{CP437, cp437_index};
+pub use diff::PixmapDiff;
+pub use dither::{Dithering, GrayDepth, GrayPixmap};
+pub use glyph_renderer::{CellText, GlyphContent, GlyphImage, GlyphRenderer};
+pub use pixmap::{PixelRect, RgbPixmap};
+pub use screen_text::{ScreenText, TextLine, TextRun};
+pub use selection::{Selection, SelectionMode};
+pub use subpixel::{Antialiasing, SubpixelOrder};
+pub use soft_backend::{Accessibility, Letterbox, PixelFit, SoftBackend, ZoomPolicy};
+pub use tileset::{Tileset, TilesetError};
+mod bitmap_font;
+mod blink;
+mod cell_cache;
+mod collections;
 mod colors;
+#[cfg(feature = "cosmic")]
+mod cosmic_renderer;
+mod cp437;
+mod diff;
+mod dither;
+#[cfg(feature = "embedded-graphics")]
+mod embedded;
+mod escape;
+mod glyph_renderer;
+mod hyperlink;
+mod inline_image;
+mod math;
 
 mod pixmap;
+mod screen_text;
+mod selection;
+mod subpixel;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod tileset;
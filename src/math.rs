@@ -0,0 +1,21 @@
+//! Float functions that `core` lacks, taken from `std` when available and from `libm` otherwise.
+
+#[cfg(feature = "std")]
+pub fn round(x: f32) -> f32 {
+    x.round()
+}
+
+#[cfg(not(feature = "std"))]
+pub fn round(x: f32) -> f32 {
+    libm::roundf(x)
+}
+
+#[cfg(feature = "std")]
+pub fn powf(x: f32, n: f32) -> f32 {
+    x.powf(n)
+}
+
+#[cfg(not(feature = "std"))]
+pub fn powf(x: f32, n: f32) -> f32 {
+    libm::powf(x, n)
+}
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 /// A pixmap with RGB pixels stored in a flat vector.
 #[derive(Debug, Clone)]
 pub struct RgbPixmap {
@@ -29,7 +31,6 @@ impl RgbPixmap {
     }
 
     /// Sets the RGB value of a pixel at (x, y).
-
     pub fn put_pixel(&mut self, x: usize, y: usize, color: [u8; 3]) {
         debug_assert!(
             x < self.width && y < self.height,
@@ -40,7 +41,6 @@ impl RgbPixmap {
     }
 
     /// Returns the RGB value of a pixel at (x, y).
-
     pub fn get_pixel(&self, x: usize, y: usize) -> [u8; 3] {
         debug_assert!(
             x < self.width && y < self.height,
@@ -59,6 +59,22 @@ impl RgbPixmap {
         }
     }
 
+    /// Fills a rectangle of the pixmap with the specified RGB color, clipped to the pixmap bounds.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: [u8; 3]) {
+        let x_end = (x + width).min(self.width);
+        let y_end = (y + height).min(self.height);
+        if x >= x_end {
+            return;
+        }
+        for row in y..y_end {
+            let start = 3 * (row * self.width + x);
+            let end = 3 * (row * self.width + x_end);
+            for chunk in self.data[start..end].chunks_exact_mut(3) {
+                chunk.copy_from_slice(&color);
+            }
+        }
+    }
+
     /// Returns the width of the pixmap in pixels
     pub fn width(&self) -> usize {
         self.width
@@ -72,4 +88,187 @@ impl RgbPixmap {
     pub fn data(&self) -> &[u8] {
         &self.data
     }
+
+    /// Returns the raw rgb data of the pixmap as a mutable flat array
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// Borrows the whole pixmap as a single band.
+    pub(crate) fn as_band(&mut self) -> PixmapBand<'_> {
+        PixmapBand {
+            width: self.width,
+            top: 0,
+            height: self.height,
+            data: &mut self.data,
+        }
+    }
+
+    /// Splits the pixmap into bands of `rows` pixel rows that can be drawn into concurrently.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn par_bands(
+        &mut self,
+        rows: usize,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = PixmapBand<'_>> {
+        use rayon::prelude::*;
+
+        let width = self.width;
+        self.data
+            .par_chunks_mut(3 * width * rows)
+            .enumerate()
+            .map(move |(index, data)| PixmapBand {
+                width,
+                top: index * rows,
+                height: data.len() / (3 * width),
+                data,
+            })
+    }
+}
+
+/// A rectangle in pixmap coordinates, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PixelRect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl PixelRect {
+    /// Returns the smallest rectangle containing both rectangles.
+    pub fn union(self, other: Self) -> Self {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        Self {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        }
+    }
+
+    /// Returns true when the rectangles overlap or share an edge.
+    fn touches(&self, other: &Self) -> bool {
+        self.x <= other.x + other.width
+            && other.x <= self.x + self.width
+            && self.y <= other.y + other.height
+            && other.y <= self.y + self.height
+    }
+
+    /// Widens the rectangle so its left and right edges fall on multiples of `multiple` pixels,
+    /// without growing past `max_width`.
+    fn align_x(self, multiple: usize, max_width: usize) -> Self {
+        let multiple = multiple.max(1);
+        let x = self.x - self.x % multiple;
+        let right = (self.x + self.width).next_multiple_of(multiple).min(max_width);
+        Self {
+            x,
+            width: right - x,
+            ..self
+        }
+    }
+}
+
+/// Maximum number of separate rectangles kept by [`DirtyRects`] before they are collapsed into one.
+const MAX_DIRTY_RECTS: usize = 32;
+
+/// Collects the areas of a pixmap changed since they were last taken.
+/// Touching rectangles are merged, so a full redraw ends up as a single rectangle.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DirtyRects {
+    rects: Vec<PixelRect>,
+}
+
+impl DirtyRects {
+    /// Marks an area as changed, empty rectangles are ignored.
+    pub fn add(&mut self, mut rect: PixelRect) {
+        if rect.width == 0 || rect.height == 0 {
+            return;
+        }
+        while let Some(index) = self.rects.iter().position(|other| other.touches(&rect)) {
+            rect = rect.union(self.rects.swap_remove(index));
+        }
+        self.rects.push(rect);
+        if self.rects.len() > MAX_DIRTY_RECTS {
+            let bounds = self.rects.drain(..).reduce(PixelRect::union);
+            self.rects.extend(bounds);
+        }
+    }
+
+    /// Returns the changed areas with their horizontal edges aligned to `align` pixels, and forgets them.
+    pub fn take(&mut self, align: usize, max_width: usize) -> Vec<PixelRect> {
+        let mut aligned = DirtyRects::default();
+        for rect in self.rects.drain(..) {
+            aligned.add(rect.align_x(align, max_width));
+        }
+        aligned.rects
+    }
+}
+
+/// Enlarges an image stored with `channels` bytes per pixel by a whole number,
+/// repeating every pixel `scale` times in both directions.
+pub(crate) fn upscale(data: &[u8], width: usize, channels: usize, scale: usize) -> Vec<u8> {
+    if scale == 1 {
+        return data.to_vec();
+    }
+    let row_bytes = width * channels * scale;
+    let mut scaled = Vec::with_capacity(data.len() * scale * scale);
+    for row in data.chunks_exact(width * channels) {
+        let start = scaled.len();
+        for pixel in row.chunks_exact(channels) {
+            for _ in 0..scale {
+                scaled.extend_from_slice(pixel);
+            }
+        }
+        for _ in 1..scale {
+            scaled.extend_from_within(start..start + row_bytes);
+        }
+    }
+    scaled
+}
+
+/// A horizontal band of pixel rows borrowed from a [`RgbPixmap`].
+/// Coordinates stay in pixmap space, drawing outside of the band's rows is skipped.
+pub(crate) struct PixmapBand<'a> {
+    data: &'a mut [u8],
+    width: usize,
+    top: usize,
+    height: usize,
+}
+
+impl PixmapBand<'_> {
+    /// Clips the rows `y..y + height` to the band, returning the visible range.
+    fn visible_rows(&self, y: usize, height: usize) -> core::ops::Range<usize> {
+        y.max(self.top)..(y + height).min(self.top + self.height)
+    }
+
+    /// Fills a rectangle with the specified RGB color, the columns must lie inside the pixmap.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: [u8; 3]) {
+        for row in self.visible_rows(y, height) {
+            let start = 3 * ((row - self.top) * self.width + x);
+            for chunk in self.data[start..start + 3 * width].chunks_exact_mut(3) {
+                chunk.copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Copies `columns` pixels of each row of an rgb image that is `width` pixels wide,
+    /// with its top-left corner at (x, y). The columns must lie inside the pixmap.
+    pub fn copy_from(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        columns: usize,
+        rows: usize,
+        data: &[u8],
+    ) {
+        for row in self.visible_rows(y, rows) {
+            let src = 3 * (row - y) * width;
+            let dst = 3 * ((row - self.top) * self.width + x);
+            self.data[dst..dst + 3 * columns].copy_from_slice(&data[src..src + 3 * columns]);
+        }
+    }
 }
@@ -0,0 +1,145 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use ratatui_core::buffer::{Buffer, Cell, CellWidth};
+use ratatui_core::layout::Position;
+use ratatui_core::style::{Modifier, Style};
+
+use crate::hyperlink::display_symbol;
+
+/// The visible text of the grid, for screen readers and other assistive technology,
+/// see [`SoftBackend::screen_text`](crate::SoftBackend::screen_text).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScreenText {
+    /// One entry for every row of the grid, from top to bottom.
+    pub lines: Vec<TextLine>,
+    /// Rows whose text or styles changed since the previous call, all rows on the first call.
+    pub changed_lines: Vec<u16>,
+    /// Cell the cursor is on, `None` while it is hidden or outside of the grid.
+    pub cursor: Option<Position>,
+}
+
+/// The text of one row, without trailing whitespace.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TextLine {
+    pub text: String,
+    /// The text split where the style changes, e.g. to find a reversed item highlighted in a list.
+    pub runs: Vec<TextRun>,
+}
+
+/// Neighbouring cells of a row that share a style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextRun {
+    /// Cell column the run starts at. Wide characters span two columns, so it can differ from the offset in the text.
+    pub column: u16,
+    pub text: String,
+    pub style: Style,
+}
+
+/// Visits the cells of a row that start a character with their column and width,
+/// skipping the cells covered by wide characters.
+pub(crate) fn row_cells(buffer: &Buffer, y: u16) -> impl Iterator<Item = (u16, &Cell, u16)> {
+    let area = buffer.area;
+    let mut x = area.left();
+    core::iter::from_fn(move || {
+        if x >= area.right() {
+            return None;
+        }
+        let cell = &buffer[(x, y)];
+        let width = match display_symbol(cell.symbol()) {
+            Cow::Borrowed(_) => cell.cell_width(),
+            Cow::Owned(symbol) => symbol.as_str().cell_width(),
+        }
+        .max(1);
+        let column = x;
+        x = x.saturating_add(width);
+        Some((column, cell, width))
+    })
+}
+
+/// Appends the visible text of a cell, hidden text reads as spaces.
+fn push_cell_text(text: &mut String, cell: &Cell, width: u16) {
+    if cell.modifier.contains(Modifier::HIDDEN) {
+        text.extend(core::iter::repeat_n(' ', width as usize));
+    } else {
+        text.push_str(&display_symbol(cell.symbol()));
+    }
+}
+
+/// Extracts the visible text of every row of a buffer.
+/// Hidden text reads as spaces, and cells covered by a wide character are skipped.
+pub(crate) fn buffer_lines(buffer: &Buffer) -> Vec<TextLine> {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let mut runs: Vec<TextRun> = Vec::new();
+            for (x, cell, width) in row_cells(buffer, y) {
+                let style = cell.style();
+                let run = match runs.last_mut() {
+                    Some(run) if run.style == style => run,
+                    _ => {
+                        runs.push(TextRun {
+                            column: x - area.left(),
+                            text: String::new(),
+                            style,
+                        });
+                        runs.last_mut().unwrap()
+                    }
+                };
+                push_cell_text(&mut run.text, cell, width);
+            }
+
+            // Trailing whitespace carries no text, drop it together with runs left empty.
+            while let Some(run) = runs.last_mut() {
+                run.text.truncate(run.text.trim_end().len());
+                if !run.text.is_empty() {
+                    break;
+                }
+                runs.pop();
+            }
+            TextLine {
+                text: runs.iter().map(|run| run.text.as_str()).collect(),
+                runs,
+            }
+        })
+        .collect()
+}
+
+/// Returns the visible text of the given columns of a row without trailing whitespace.
+/// Wide characters are included when any of their columns is.
+pub(crate) fn row_text(buffer: &Buffer, y: u16, columns: RangeInclusive<u16>) -> String {
+    let mut text = String::new();
+    for (x, cell, width) in row_cells(buffer, y) {
+        if x <= *columns.end() && x + width > *columns.start() {
+            push_cell_text(&mut text, cell, width);
+        }
+    }
+    text.truncate(text.trim_end().len());
+    text
+}
+
+/// Returns the first and last column of the word at the given cell, `None` if the cell holds no word character.
+/// Letters, digits and the characters common in paths and URLs count as word characters.
+pub(crate) fn word_columns(buffer: &Buffer, position: Position) -> Option<(u16, u16)> {
+    const WORD_CHARS: &str = "_-.~/:@+%#=?&";
+    let is_word = |cell: &Cell| {
+        let symbol = display_symbol(cell.symbol());
+        !cell.modifier.contains(Modifier::HIDDEN)
+            && symbol.chars().all(|c| c.is_alphanumeric() || WORD_CHARS.contains(c))
+            && !symbol.is_empty()
+    };
+    let cells: Vec<(u16, &Cell, u16)> = row_cells(buffer, position.y).collect();
+    let index = cells
+        .iter()
+        .position(|&(x, _, width)| (x..x + width).contains(&position.x))?;
+    if !is_word(cells[index].1) {
+        return None;
+    }
+    let first = cells[..index].iter().rev().take_while(|cell| is_word(cell.1)).count();
+    let last = cells[index + 1..].iter().take_while(|cell| is_word(cell.1)).count();
+    let (start, _, _) = cells[index - first];
+    let (end, _, width) = cells[index + last];
+    Some((start, end + width - 1))
+}
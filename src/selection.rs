@@ -0,0 +1,66 @@
+use ratatui_core::layout::Position;
+
+/// How the cells between the two ends of a [`Selection`] are picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SelectionMode {
+    /// Follows the text like a terminal emulator: from the start to the end of the grid rows in between.
+    #[default]
+    Linear,
+    /// The rectangle spanned by the two ends, usually selected with Alt or Ctrl held.
+    Block,
+}
+
+/// A range of selected cells, see [`SoftBackend::set_selection`](crate::SoftBackend::set_selection).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Selection {
+    /// Cell the selection started at, e.g. where the mouse button was pressed.
+    pub start: Position,
+    /// Cell the selection ends at, it may lie before the start.
+    pub end: Position,
+    pub mode: SelectionMode,
+}
+
+impl Selection {
+    pub const fn new(start: Position, end: Position, mode: SelectionMode) -> Self {
+        Self { start, end, mode }
+    }
+
+    /// Returns the two ends in reading order, first by row and then by column.
+    pub fn ordered(&self) -> (Position, Position) {
+        if (self.start.y, self.start.x) <= (self.end.y, self.end.x) {
+            (self.start, self.end)
+        } else {
+            (self.end, self.start)
+        }
+    }
+
+    /// Returns the first and last selected row.
+    pub fn rows(&self) -> (u16, u16) {
+        (self.start.y.min(self.end.y), self.start.y.max(self.end.y))
+    }
+
+    /// Returns the selected columns of a row inside a grid of the given width, `None` if the row is not selected.
+    pub fn columns(&self, row: u16, width: u16) -> Option<(u16, u16)> {
+        let (top, bottom) = self.rows();
+        if row < top || row > bottom || width == 0 {
+            return None;
+        }
+        let last_column = width - 1;
+        let (first, last) = match self.mode {
+            SelectionMode::Block => (self.start.x.min(self.end.x), self.start.x.max(self.end.x)),
+            SelectionMode::Linear => {
+                let (start, end) = self.ordered();
+                let first = if row == start.y { start.x } else { 0 };
+                let last = if row == end.y { end.x } else { last_column };
+                (first, last)
+            }
+        };
+        (first <= last_column).then_some((first, last.min(last_column)))
+    }
+
+    /// Returns whether a cell is selected.
+    pub fn contains(&self, position: Position) -> bool {
+        self.columns(position.y, u16::MAX)
+            .is_some_and(|(first, last)| (first..=last).contains(&position.x))
+    }
+}
@@ -1,50 +1,334 @@
 //! This module provides the `SoftBackend` implementation for the [`Backend`] trait.
 //! It is used in the integration tests to verify the correctness of the library.
 
-use std::collections::HashSet;
-use std::io;
-
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "rayon")]
+use alloc::vec;
+use core::time::Duration;
+
+use crate::bitmap_font::BitmapFont;
+use crate::blink::BlinkTiming;
+use crate::cell_cache::{CellCache, CellKey, DEFAULT_CELL_CACHE_BYTES, PixelBlock, RenderedCell};
+use crate::collections::{HashMap, HashSet};
 use crate::colors::*;
-use crate::pixmap::RgbPixmap;
-
-use cosmic_text::fontdb::Database;
-use ratatui::backend::{Backend, WindowSize};
-use ratatui::buffer::{Buffer, Cell};
-use ratatui::layout::{Position, Rect, Size};
-use ratatui::style::Modifier;
-
-use cosmic_text::{
-    Attrs, AttrsList, CacheKeyFlags, Family, LineEnding, Metrics, Shaping, Weight, Wrap,
-};
-
-use cosmic_text::{Buffer as CosmicBuffer, FontSystem, SwashCache};
+#[cfg(feature = "bundled-font")]
+use crate::cosmic_renderer::BUNDLED_FONT;
+#[cfg(feature = "cosmic")]
+use crate::cosmic_renderer::CosmicRenderer;
+use crate::glyph_renderer::{CellText, GlyphContent, GlyphRenderer};
+use crate::escape::has_escapes;
+use crate::hyperlink::{display_symbol, row_links};
+use crate::inline_image::{ImageCommand, InlineImage, StoredImages, has_placeholders, image_commands, placeholder_count};
+use crate::math;
+use crate::pixmap::{DirtyRects, PixelRect, PixmapBand, RgbPixmap};
+use crate::screen_text::{ScreenText, TextLine, buffer_lines, row_text, word_columns};
+use crate::selection::{Selection, SelectionMode};
+use crate::subpixel::Antialiasing;
+use crate::tileset::Tileset;
+
+use ratatui_core::backend::{Backend, ClearType, WindowSize};
+use ratatui_core::buffer::{Buffer, Cell};
+use ratatui_core::layout::{Position, Rect, Size};
+use ratatui_core::style::{Color, Modifier};
+
+/// Drawing never fails, with `std` the error type stays `io::Error` like other ratatui backends.
+#[cfg(feature = "std")]
+type BackendError = std::io::Error;
+#[cfg(not(feature = "std"))]
+type BackendError = core::convert::Infallible;
 
 /// SoftBackend is a Software rendering backend for Ratatui. It stores the generated image internally as rgb_pixmap.
 pub struct SoftBackend {
     pub buffer: Buffer,
     pub cursor: bool,
     pub pos: (u16, u16),
-    font_system: FontSystem,
+    renderer: Box<dyn GlyphRenderer>,
 
-    cosmic_buffer: CosmicBuffer,
+    font_size: f32,
+    base_font_size: f32,
+    zoom_step: f32,
+    zoom_policy: ZoomPolicy,
     pub char_width: usize,
     pub char_height: usize,
+    physical_cell: (usize, usize),
+    /// Scale factor for high-DPI displays, use [`SoftBackend::set_scale_factor`] to change it.
     pub scale_factor: f32,
 
-    pub blink_counter: u16,
+    /// Whether rapidly blinking text is in its hidden phase, updated by [`SoftBackend::tick`].
     pub blinking_fast: bool,
+    /// Whether slowly blinking text is in its hidden phase, updated by [`SoftBackend::tick`].
     pub blinking_slow: bool,
-    swash_cache: SwashCache,
+    blink_clock: Duration,
+    slow_blink: BlinkTiming,
+    rapid_blink: BlinkTiming,
+    cursor_blink: Option<BlinkTiming>,
+    cursor_blink_start: Duration,
+    cursor_on: bool,
+    cell_cache: CellCache,
+    /// Forks of the renderer rasterizing large updates, one per thread of the rayon pool.
+    #[cfg(feature = "rayon")]
+    workers: Vec<std::sync::Mutex<Box<dyn GlyphRenderer>>>,
+    /// Cells of the current update rasterized by the workers ahead of painting.
+    #[cfg(feature = "rayon")]
+    prerendered: HashMap<CellKey, Arc<RenderedCell>>,
+    compositor: Compositor,
+    antialiasing: Antialiasing,
+    output_transformed: bool,
+    reset_background_transparent: bool,
+    text_blending: TextBlending,
+    text_gamma: f32,
+    text_contrast: f32,
     pub rgb_pixmap: RgbPixmap,
-    always_redraw_list: HashSet<(u16, u16)>,
+    blinking_cells: HashSet<(u16, u16)>,
+    /// Cells whose text reaches into the neighbouring cells, as of their last paint.
+    overflowing_cells: HashSet<(u16, u16)>,
+    letterbox: Option<Letterbox>,
+    window_pixels: Option<(usize, usize)>,
+    grid_offset: (usize, usize),
+    monochrome: bool,
+    dirty_rects: DirtyRects,
+    accessibility: Accessibility,
+    last_screen_text: Option<Vec<TextLine>>,
+    selection: Option<Selection>,
+    hovered_cell: Option<Position>,
+    /// Row and first and last column of the hyperlink under the hovered cell.
+    hovered_link: Option<(u16, u16, u16)>,
+    images: Vec<ImagePlacement>,
+    /// Images transmitted with the Kitty graphics protocol, by id, for later placements.
+    kitty_images: StoredImages,
+}
+
+/// Describes how [`SoftBackend::resize_to_pixels`] places the grid inside a larger pixel area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Letterbox {
+    /// Centers the grid inside the area, otherwise it is pinned to the top-left corner.
+    pub centered: bool,
+    /// Color of the padding surrounding the grid.
+    pub padding_color: [u8; 3],
+}
+
+impl Default for Letterbox {
+    fn default() -> Self {
+        Self {
+            centered: true,
+            padding_color: [24, 24, 24],
+        }
+    }
+}
+
+/// Accessibility settings applied to every cell without the application changing its styles,
+/// see [`SoftBackend::set_accessibility`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Accessibility {
+    /// Draws blinking text and the cursor steadily, for photosensitive users.
+    pub disable_blinking: bool,
+    /// Minimum WCAG contrast ratio between text and its background, from 1.0 to 21.0.
+    /// Text below it is lightened or darkened, 4.5 matches WCAG AA and 7.0 WCAG AAA for normal text.
+    pub min_contrast: Option<f32>,
+    /// Replaces the 16 named colors in ANSI order from `Black` to `White`, including `Indexed` 0 to 15.
+    /// `Reset` uses the `Black` entry as background and the `White` entry as foreground.
+    pub palette: Option<[[u8; 3]; 16]>,
+}
+
+impl Accessibility {
+    /// Bright colors that stay readable on black, with a black background and white text for `Reset`.
+    pub const HIGH_CONTRAST_PALETTE: [[u8; 3]; 16] = [
+        [0, 0, 0],
+        [255, 96, 96],
+        [96, 255, 96],
+        [255, 255, 0],
+        [128, 160, 255],
+        [255, 96, 255],
+        [0, 255, 255],
+        [208, 208, 208],
+        [160, 160, 160],
+        [255, 160, 160],
+        [160, 255, 160],
+        [255, 255, 176],
+        [176, 208, 255],
+        [255, 176, 255],
+        [176, 255, 255],
+        [255, 255, 255],
+    ];
+
+    /// No blinking, the high contrast palette and a minimum contrast of 7:1.
+    pub const fn high_contrast() -> Self {
+        Self {
+            disable_blinking: true,
+            min_contrast: Some(7.0),
+            palette: Some(Self::HIGH_CONTRAST_PALETTE),
+        }
+    }
+}
+
+/// Decides what stays stable when zooming with [`SoftBackend::zoom_in`] and [`SoftBackend::zoom_out`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZoomPolicy {
+    /// Keeps the number of columns and rows, the pixmap grows or shrinks with the cells.
+    #[default]
+    PreserveGrid,
+    /// Keeps the pixel size of the pixmap, the grid gains or loses columns and rows.
+    PreservePixels,
+}
+
+/// The result of fitting the grid into a pixel area with [`SoftBackend::resize_to_pixels`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelFit {
+    /// Number of columns that fit into the area
+    pub columns: u16,
+    /// Number of rows that fit into the area
+    pub rows: u16,
+    /// Horizontal pixels left over after placing the grid
+    pub margin_x: usize,
+    /// Vertical pixels left over after placing the grid
+    pub margin_y: usize,
+}
+
+/// Minimum number of cells in an update before rasterizing and painting are split across threads.
+#[cfg(feature = "rayon")]
+const PARALLEL_MIN_CELLS: usize = 512;
+
+/// Height of a band painted by one thread, in cell rows.
+#[cfg(feature = "rayon")]
+const BAND_CELL_ROWS: usize = 2;
+
+/// An image drawn over a rectangle of cells, it stays until one of the cells changes.
+struct ImagePlacement {
+    area: Rect,
+    image: Arc<InlineImage>,
+    /// Stretches the image over the whole area, otherwise it keeps its size in logical pixels.
+    stretch: bool,
+    /// The image resized to its size in the pixmap, when that differs from the original,
+    /// with that size. Only the part inside the area is kept.
+    scaled: Option<((usize, usize), InlineImage)>,
+}
+
+/// Everything needed to paint one cell, resolved up front so painting can run without the font system.
+struct CellPaint {
+    x: usize,
+    y: usize,
+    /// `None` when only the text is drawn again, on top of repainted neighbouring cells.
+    background: Option<[u8; 3]>,
+    text: Arc<RenderedCell>,
+    /// Text pixels outside of this area are skipped.
+    clip: PixelRect,
+}
+
+impl CellPaint {
+    /// Returns the area of the pixmap the cell paints.
+    fn painted_area(&self, cell_size: (usize, usize), clip_end: (usize, usize)) -> PixelRect {
+        let (right, bottom) = self.text.extent();
+        let x = self.x.max(self.clip.x);
+        let y = self.y.max(self.clip.y);
+        let text = PixelRect {
+            x,
+            y,
+            width: (self.x + right).min(self.clip.x + self.clip.width).saturating_sub(x),
+            height: (self.y + bottom).min(self.clip.y + self.clip.height).saturating_sub(y),
+        };
+        match self.background {
+            Some(_) if text.width == 0 || text.height == 0 => self.cell_area(cell_size, clip_end),
+            Some(_) => text.union(self.cell_area(cell_size, clip_end)),
+            None => text,
+        }
+    }
+
+    /// Returns the area of the cell itself, clipped to the grid.
+    fn cell_area(&self, cell_size: (usize, usize), clip_end: (usize, usize)) -> PixelRect {
+        PixelRect {
+            x: self.x,
+            y: self.y,
+            width: cell_size.0.min(clip_end.0 - self.x),
+            height: cell_size.1.min(clip_end.1 - self.y),
+        }
+    }
+}
+
+fn paint_band<'a>(
+    band: &mut PixmapBand,
+    cells: impl Iterator<Item = &'a CellPaint> + Clone,
+    cell_size: (usize, usize),
+    clip_end: (usize, usize),
+) {
+    // First pass: draw backgrounds
+    for cell in cells.clone() {
+        if let Some(background) = cell.background {
+            let area = cell.cell_area(cell_size, clip_end);
+            band.fill_rect(area.x, area.y, area.width, area.height, background);
+        }
+    }
+
+    // Second pass: draw text (allows overflow)
+    for cell in cells {
+        cell.text.blit(band, cell.x, cell.y, cell.clip);
+    }
 }
 
-fn add_strikeout(text: &String) -> String {
+/// Rasterizes the text of a cell, blending the glyph images over the background color.
+fn render_cell_text(renderer: &mut dyn GlyphRenderer, compositor: &Compositor, key: &CellKey) -> RenderedCell {
+    let images = renderer.render(&CellText {
+        symbol: &key.symbol,
+        modifier: key.modifier,
+        fg: key.fg,
+        bg: key.bg,
+        antialiasing: key.antialiasing,
+    });
+
+    let mut rendered = RenderedCell::default();
+    for image in images {
+        // Glyph renderers can be user code, a wrong length would panic or draw garbage further down.
+        let expected_len = image
+            .width
+            .checked_mul(image.height)
+            .and_then(|pixels| pixels.checked_mul(image.content.bytes_per_pixel()));
+        debug_assert_eq!(expected_len, Some(image.data.len()), "glyph image data does not match its size");
+        if expected_len != Some(image.data.len()) {
+            continue;
+        }
+        let glyph_width = image.width as i32;
+        let glyph_height = image.height as i32;
+
+        // Pixels above or left of the cell origin are not drawn.
+        let skip_x = (-image.left).clamp(0, glyph_width);
+        let skip_y = (-image.top).clamp(0, glyph_height);
+        let width = (glyph_width - skip_x) as usize;
+        let height = (glyph_height - skip_y) as usize;
+
+        let mut data = Vec::with_capacity(3 * width * height);
+        for off_y in skip_y..glyph_height {
+            let row = (off_y * glyph_width) as usize;
+            let (start, end) = (row + skip_x as usize, row + glyph_width as usize);
+            match image.content {
+                GlyphContent::SubpixelMask => {
+                    compositor.blend_row_subpixel(key.fg, key.bg, &image.data[4 * start..4 * end], &mut data)
+                }
+                GlyphContent::Color => compositor.blend_row_color(key.bg, &image.data[4 * start..4 * end], &mut data),
+                GlyphContent::Mask if key.antialiasing == Antialiasing::None => {
+                    compositor.blend_row_aliased(key.fg, key.bg, &image.data[start..end], &mut data)
+                }
+                GlyphContent::Mask => compositor.blend_row(key.fg, key.bg, &image.data[start..end], &mut data),
+            }
+        }
+        rendered.blocks.push(PixelBlock {
+            x: (image.left + skip_x) as usize,
+            y: (image.top + skip_y) as usize,
+            width,
+            height,
+            data,
+        });
+    }
+    rendered
+}
+
+fn add_strikeout(text: &str) -> String {
     let strike = '\u{0336}';
     text.chars().flat_map(|c| [c, strike]).collect()
 }
 
-fn add_underline(text: &String) -> String {
+fn add_underline(text: &str) -> String {
     let strike = '\u{0332}';
     text.chars().flat_map(|c| [c, strike]).collect()
 }
@@ -67,213 +351,1084 @@ impl SoftBackend {
         self.rgb_pixmap.height()
     }
 
-    fn draw_cell_background(&mut self, xik: u16, yik: u16) {
-        let physical_char_width = (self.char_width as f32 * self.scale_factor) as usize;
-        let physical_char_height = (self.char_height as f32 * self.scale_factor) as usize;
-        let begin_x = xik as usize * physical_char_width;
-        let begin_y = yik as usize * physical_char_height;
-        
+    /// Returns the size of a single cell in physical pixels
+    fn physical_cell_size(&self) -> (usize, usize) {
+        self.physical_cell
+    }
+
+    /// Returns the pixel offset of the grid inside the pixmap, non-zero when letterboxing.
+    pub fn grid_offset(&self) -> (usize, usize) {
+        self.grid_offset
+    }
+
+    /// Returns the exclusive bottom-right corner of the grid in pixmap coordinates.
+    fn grid_end(&self) -> (usize, usize) {
+        let (physical_char_width, physical_char_height) = self.physical_cell_size();
+        let grid_width = physical_char_width * self.buffer.area.width as usize;
+        let grid_height = physical_char_height * self.buffer.area.height as usize;
+        (
+            (self.grid_offset.0 + grid_width).min(self.rgb_pixmap.width()),
+            (self.grid_offset.1 + grid_height).min(self.rgb_pixmap.height()),
+        )
+    }
+
+    /// Resolves the background color of a cell and everything its text is rendered from,
+    /// and keeps track of blinking cells. Returns `None` for cells lying entirely outside of the pixmap.
+    fn cell_style(&mut self, xik: u16, yik: u16) -> Option<([u8; 3], CellKey)> {
+        let (physical_char_width, physical_char_height) = self.physical_cell_size();
+        let begin_x = self.grid_offset.0 + xik as usize * physical_char_width;
+        let begin_y = self.grid_offset.1 + yik as usize * physical_char_height;
+        let (pixmap_width, pixmap_height) = self.grid_end();
+
+        let rat_cell = self.buffer.cell(Position::new(xik, yik)).unwrap();
+        let mut modifier = rat_cell.modifier;
+        if modifier.intersects(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK) {
+            self.blinking_cells.insert((xik, yik));
+        } else {
+            self.blinking_cells.remove(&(xik, yik));
+        }
+        // The cursor and the selection are drawn by swapping the colors of the cells under them.
+        if self.cursor && self.cursor_on && self.pos == (xik, yik) {
+            modifier ^= Modifier::REVERSED;
+        }
+        if self.selection.is_some_and(|selection| selection.contains(Position::new(xik, yik))) {
+            modifier ^= Modifier::REVERSED;
+        }
+        if self
+            .hovered_link
+            .is_some_and(|(row, first, last)| row == yik && (first..=last).contains(&xik))
+        {
+            modifier |= Modifier::UNDERLINED;
+        }
+
         // Early bounds check to prevent drawing cells that would be entirely out of bounds
-        if begin_x >= self.rgb_pixmap.width() || begin_y >= self.rgb_pixmap.height() {
-            return;
+        if begin_x >= pixmap_width || begin_y >= pixmap_height {
+            return None;
         }
-        
-        let rat_cell = self.buffer.cell(Position::new(xik, yik)).unwrap();
-        
+
         let rat_bg = rat_cell.bg;
-        let bg_color = if rat_cell.modifier.contains(Modifier::REVERSED) {
+        let background = if modifier.contains(Modifier::REVERSED) {
             let rat_fg = rat_cell.fg;
-            rat_to_rgb(&rat_fg, true)
+            self.color_rgb(&rat_fg, true)
         } else {
-            rat_to_rgb(&rat_bg, false)
+            self.color_rgb(&rat_bg, false)
         };
-        
-        let bg_color = if rat_cell.modifier.contains(Modifier::DIM) {
-            dim_rgb(bg_color)
+
+        let mut background = if modifier.contains(Modifier::DIM) {
+            dim_rgb(background)
         } else {
-            bg_color
+            background
         };
 
-        let pixmap_width = self.rgb_pixmap.width();
-        let pixmap_height = self.rgb_pixmap.height();
-        for y in 0..physical_char_height {
-            for x in 0..physical_char_width {
-                let px = begin_x + x;
-                let py = begin_y + y;
-                if px < pixmap_width && py < pixmap_height {
-                    self.rgb_pixmap.put_pixel(px, py, bg_color);
-                }
-            }
-        }
-    }
-
-    fn draw_cell_text(&mut self, xik: u16, yik: u16) {
-        let physical_char_width = (self.char_width as f32 * self.scale_factor) as usize;
-        let physical_char_height = (self.char_height as f32 * self.scale_factor) as usize;
-        let begin_x = xik as usize * physical_char_width;
-        let begin_y = yik as usize * physical_char_height;
-        
-        let rat_cell = self.buffer.cell(Position::new(xik, yik)).unwrap();
-
         let mut rat_fg = rat_cell.fg;
-        let rat_bg = rat_cell.bg;
-        if rat_cell.modifier.contains(Modifier::HIDDEN) {
+        if modifier.contains(Modifier::HIDDEN) {
             rat_fg = rat_bg;
         }
 
-        let (mut fg_color, bg_color) = if rat_cell.modifier.contains(Modifier::REVERSED) {
-            (rat_to_rgb(&rat_bg, false), rat_to_rgb(&rat_fg, true))
+        let (mut fg_color, mut bg_color) = if modifier.contains(Modifier::REVERSED) {
+            (self.color_rgb(&rat_bg, false), self.color_rgb(&rat_fg, true))
         } else {
-            (rat_to_rgb(&rat_fg, true), rat_to_rgb(&rat_bg, false))
+            (self.color_rgb(&rat_fg, true), self.color_rgb(&rat_bg, false))
         };
 
-        if rat_cell.modifier.contains(Modifier::DIM) {
+        if modifier.contains(Modifier::DIM) {
             fg_color = dim_rgb(fg_color);
+            // Glyphs are blended over the dimmed background they are drawn on.
+            bg_color = dim_rgb(bg_color);
         };
 
-        let pixmap_width = self.rgb_pixmap.width();
-        let pixmap_height = self.rgb_pixmap.height();
+        let invisible = modifier.contains(Modifier::HIDDEN) || fg_color == bg_color;
+        if let Some(min_contrast) = self.accessibility.min_contrast
+            && !invisible
+        {
+            fg_color = enforce_contrast(fg_color, bg_color, min_contrast);
+        }
 
-        let mut text_symbol: String = rat_cell.symbol().to_string();
+        if self.monochrome {
+            background = mono_rgb(background);
+            bg_color = mono_rgb(bg_color);
+            fg_color = if invisible { bg_color } else { mono_fg_rgb(fg_color, bg_color) };
+        }
 
-        if rat_cell.modifier.contains(Modifier::CROSSED_OUT) {
+        let mut text_symbol: String = display_symbol(rat_cell.symbol()).into_owned();
+
+        if modifier.contains(Modifier::CROSSED_OUT) {
             text_symbol = add_strikeout(&text_symbol);
         }
-        if rat_cell.modifier.contains(Modifier::UNDERLINED) {
+        if modifier.contains(Modifier::UNDERLINED) {
             text_symbol = add_underline(&text_symbol);
         }
 
-        if rat_cell.modifier.contains(Modifier::SLOW_BLINK) {
-            self.always_redraw_list.insert((xik, yik));
-            if self.blinking_slow {
-                fg_color = bg_color.clone();
-            }
+        let blinked_off = (modifier.contains(Modifier::SLOW_BLINK) && self.blinking_slow)
+            || (modifier.contains(Modifier::RAPID_BLINK) && self.blinking_fast);
+        if blinked_off {
+            fg_color = bg_color;
+        }
+        // Tiles and color glyphs are not drawn in the foreground color alone, so invisible text is dropped instead.
+        if blinked_off || modifier.contains(Modifier::HIDDEN) {
+            text_symbol.clear();
         }
-        if rat_cell.modifier.contains(Modifier::RAPID_BLINK) {
-            self.always_redraw_list.insert((xik, yik));
-            if self.blinking_fast {
-                fg_color = bg_color.clone();
+
+        let key = CellKey::new(text_symbol, modifier, fg_color, bg_color, self.cell_antialiasing(rat_cell.bg));
+        Some((background, key))
+    }
+
+    /// Resolves the colors of a cell and makes sure its text is rendered, so that painting needs no font access.
+    /// Returns `None` for cells lying entirely outside of the pixmap.
+    fn prepare_cell(&mut self, xik: u16, yik: u16) -> Option<CellPaint> {
+        let (background, key) = self.cell_style(xik, yik)?;
+        let (physical_char_width, physical_char_height) = self.physical_cell_size();
+        let begin_x = self.grid_offset.0 + xik as usize * physical_char_width;
+        let begin_y = self.grid_offset.1 + yik as usize * physical_char_height;
+        let (pixmap_width, pixmap_height) = self.grid_end();
+
+        let text = match self.cell_cache.get(&key) {
+            Some(text) => text,
+            None => {
+                let text = self.render_text(&key);
+                self.cell_cache.insert(key, text.clone());
+                text
             }
+        };
+        let (right, bottom) = text.extent();
+        if right > physical_char_width || bottom > physical_char_height {
+            self.overflowing_cells.insert((xik, yik));
+        } else {
+            self.overflowing_cells.remove(&(xik, yik));
         }
 
-        let mut attrs = Attrs::new().family(Family::Monospace);
-        if rat_cell.modifier.contains(Modifier::BOLD) {
-            attrs = attrs.weight(Weight::BOLD);
+        let (offset_x, offset_y) = self.grid_offset;
+        Some(CellPaint {
+            x: begin_x,
+            y: begin_y,
+            background: Some(background),
+            text,
+            clip: PixelRect {
+                x: offset_x,
+                y: offset_y,
+                width: pixmap_width - offset_x,
+                height: pixmap_height - offset_y,
+            },
+        })
+    }
+
+    /// Rasterizes the text of a cell missing from the cache, unless a worker already did.
+    fn render_text(&mut self, key: &CellKey) -> Arc<RenderedCell> {
+        #[cfg(feature = "rayon")]
+        if let Some(text) = self.prerendered.get(key) {
+            return text.clone();
+        }
+        Arc::new(render_cell_text(self.renderer.as_mut(), &self.compositor, key))
+    }
+
+    /// Prepares a partial update of the given cells, giving the same pixels as a full redraw.
+    ///
+    /// Glyphs may overflow into the cells right of and below them. When the old or the new text of a changed cell
+    /// overflows, those cells are repainted as well to remove overflow of the previous text. The overflow of the cells
+    /// around the repainted ones is then drawn again on top, clipped to the repainted cells, in the same order as
+    /// [`SoftBackend::redraw`]. Without overflowing text only the changed cells are painted.
+    fn prepare_update(&mut self, changed: impl IntoIterator<Item = (u16, u16)>) -> Vec<CellPaint> {
+        let changed: Vec<(u16, u16)> = changed.into_iter().collect();
+        #[cfg(feature = "rayon")]
+        self.prerender(&changed);
+
+        let area = self.buffer.area;
+        let in_grid = |x: u16, y: u16| x < area.width && y < area.height;
+        let mut prepared = HashMap::new();
+        let mut repaint = HashSet::new();
+        for (x, y) in changed {
+            if !in_grid(x, y) || prepared.contains_key(&(x, y)) {
+                continue;
+            }
+            let overflowed = self.overflowing_cells.contains(&(x, y));
+            prepared.insert((x, y), self.prepare_cell(x, y));
+            repaint.insert((x, y));
+            if overflowed || self.overflowing_cells.contains(&(x, y)) {
+                for (dx, dy) in [(1, 0), (0, 1), (1, 1)] {
+                    if let (Some(x), Some(y)) = (x.checked_add(dx), y.checked_add(dy))
+                        && in_grid(x, y)
+                    {
+                        repaint.insert((x, y));
+                    }
+                }
+            }
+        }
+        let mut order: Vec<(u16, u16)> = repaint.iter().copied().collect();
+        for &(x, y) in &repaint {
+            for (dx, dy) in [(1, 0), (0, 1), (1, 1)] {
+                if let (Some(x), Some(y)) = (x.checked_sub(dx), y.checked_sub(dy))
+                    && self.overflowing_cells.contains(&(x, y))
+                {
+                    order.push((x, y));
+                }
+            }
         }
-        if rat_cell.modifier.contains(Modifier::ITALIC) {
-            attrs = attrs.cache_key_flags(CacheKeyFlags::FAKE_ITALIC);
+        // Column by column like a full redraw, so overlapping glyphs stack the same way.
+        order.sort_unstable();
+        order.dedup();
+
+        let (cell_width, cell_height) = self.physical_cell_size();
+        let (clip_end_x, clip_end_y) = self.grid_end();
+        let mut cells = Vec::with_capacity(order.len());
+        for (x, y) in order {
+            let cell = match prepared.remove(&(x, y)) {
+                Some(cell) => cell,
+                None => self.prepare_cell(x, y),
+            };
+            let Some(cell) = cell else {
+                continue;
+            };
+            // Text that stays inside its cell is only drawn together with its background.
+            if !self.overflowing_cells.contains(&(x, y)) {
+                if repaint.contains(&(x, y)) {
+                    cells.push(cell);
+                }
+                continue;
+            }
+            // The repainted columns among this cell and its right neighbour, in this row and the one below.
+            let columns = |row: u16| {
+                let mut columns = (x..=x.saturating_add(1)).filter(|&column| repaint.contains(&(column, row)));
+                let first = columns.next()?;
+                Some((first, columns.next_back().unwrap_or(first)))
+            };
+            let rows = match (columns(y), columns(y.saturating_add(1))) {
+                (Some(top), Some(bottom)) if top == bottom => [Some((y, 2, top)), None],
+                (top, bottom) => [top.map(|top| (y, 1, top)), bottom.map(|bottom| (y + 1, 1, bottom))],
+            };
+            let mut background = repaint.contains(&(x, y)).then_some(cell.background).flatten();
+            for (row, row_count, (first, last)) in rows.into_iter().flatten() {
+                let clip_x = self.grid_offset.0 + first as usize * cell_width;
+                let clip_y = self.grid_offset.1 + row as usize * cell_height;
+                cells.push(CellPaint {
+                    x: cell.x,
+                    y: cell.y,
+                    background: background.take(),
+                    text: cell.text.clone(),
+                    clip: PixelRect {
+                        x: clip_x,
+                        y: clip_y,
+                        width: ((last - first + 1) as usize * cell_width).min(clip_end_x - clip_x),
+                        height: (row_count * cell_height).min(clip_end_y - clip_y),
+                    },
+                });
+            }
         }
-        let mets = self.cosmic_buffer.metrics().font_size;
-        let line = self.cosmic_buffer.lines.get_mut(0).unwrap();
-        line.set_text(&text_symbol, LineEnding::None, AttrsList::new(&attrs));
+        #[cfg(feature = "rayon")]
+        self.prerendered.clear();
+        cells
+    }
 
-        line.layout(&mut self.font_system, mets, None, Wrap::None, None, 1);
+    /// Rasterizes the cells of a large update missing from the cache on all threads, in bands of rows like painting.
+    /// Every thread renders with its own fork of the renderer, the results are merged for [`SoftBackend::prepare_cell`].
+    #[cfg(feature = "rayon")]
+    fn prerender(&mut self, changed: &[(u16, u16)]) {
+        use rayon::prelude::*;
 
-        for run in self.cosmic_buffer.layout_runs() {
-            for glyph in run.glyphs.iter() {
-                let physical_glyph = glyph.physical((0., 0.), self.scale_factor);
+        if changed.len() < PARALLEL_MIN_CELLS || !self.fork_workers() {
+            return;
+        }
+        let mut bands: Vec<Vec<CellKey>> = Vec::new();
+        let mut missing = HashSet::new();
+        for &(x, y) in changed {
+            let Some((_, key)) = self.cell_style(x, y) else {
+                continue;
+            };
+            if self.cell_cache.contains(&key) || !missing.insert(key.clone()) {
+                continue;
+            }
+            let band = y as usize / BAND_CELL_ROWS;
+            if bands.len() <= band {
+                bands.resize_with(band + 1, Vec::new);
+            }
+            bands[band].push(key);
+        }
 
-                //TODO : Handle Content::Color (emojis?)
+        let workers = &self.workers;
+        let compositor = &self.compositor;
+        let rendered: Vec<Vec<(CellKey, Arc<RenderedCell>)>> = bands
+            .into_par_iter()
+            .map(|keys| {
+                // Every thread of the pool locks a fork of its own.
+                let worker = rayon::current_thread_index().unwrap_or(0) % workers.len();
+                let mut renderer = workers[worker].lock().unwrap();
+                keys.into_iter()
+                    .map(|key| {
+                        let text = Arc::new(render_cell_text(renderer.as_mut(), compositor, &key));
+                        (key, text)
+                    })
+                    .collect()
+            })
+            .collect();
+        self.prerendered.extend(rendered.into_iter().flatten());
+    }
 
-                if let Some(image) = self
-                    .swash_cache
-                    .get_image(&mut self.font_system, physical_glyph.cache_key)
-                {
-                    //    println!("imagik {:#?}", image.data.len());
-                    let x = image.placement.left;
+    /// Gives every thread of the rayon pool its own fork of the renderer.
+    /// Returns false for renderers that can not be forked.
+    #[cfg(feature = "rayon")]
+    fn fork_workers(&mut self) -> bool {
+        while self.workers.len() < rayon::current_num_threads() {
+            let Some(mut fork) = self.renderer.fork() else {
+                return false;
+            };
+            fork.set_size(self.font_size, self.scale_factor);
+            self.workers.push(std::sync::Mutex::new(fork));
+        }
+        true
+    }
 
-                    let y = -image.placement.top;
-                    let mut i = 0;
+    /// Paints prepared cells into the pixmap: all backgrounds first, then all text, so text can overflow into neighbouring cells.
+    /// With the `rayon` feature, large updates are painted concurrently in bands of rows with the same result.
+    fn paint_cells(&mut self, cells: &[CellPaint]) {
+        let cell_size = self.physical_cell_size();
+        let clip_end = self.grid_end();
 
-                    for off_y in 0..image.placement.height {
-                        for off_x in 0..image.placement.width {
-                            let real_x = physical_glyph.x + x + off_x as i32;
+        for cell in cells {
+            self.dirty_rects.add(cell.painted_area(cell_size, clip_end));
+        }
 
-                            let real_y = run.line_y as i32 + physical_glyph.y + y + off_y as i32;
+        #[cfg(feature = "rayon")]
+        if cells.len() >= PARALLEL_MIN_CELLS {
+            use rayon::prelude::*;
+
+            // Every band paints the cells touching it in the original order,
+            // so overlapping glyphs end up exactly like in the serial path.
+            let band_rows = (cell_size.1 * BAND_CELL_ROWS).max(1);
+            let band_count = self.rgb_pixmap.height().div_ceil(band_rows);
+            let mut buckets: Vec<Vec<&CellPaint>> = vec![Vec::new(); band_count];
+            for cell in cells {
+                let area = cell.painted_area(cell_size, clip_end);
+                for bucket in &mut buckets[area.y / band_rows..(area.y + area.height).div_ceil(band_rows)] {
+                    bucket.push(cell);
+                }
+            }
+            self.rgb_pixmap
+                .par_bands(band_rows)
+                .zip(buckets)
+                .for_each(|(mut band, bucket)| {
+                    paint_band(&mut band, bucket.into_iter(), cell_size, clip_end);
+                });
+            self.paint_images(cells);
+            return;
+        }
 
-                            if real_x >= 0 && real_y >= 0 {
-                                let get_x = begin_x + real_x as usize;
-                                let get_y = begin_y + real_y as usize;
+        paint_band(&mut self.rgb_pixmap.as_band(), cells.iter(), cell_size, clip_end);
+        self.paint_images(cells);
+    }
 
-                                if get_x < pixmap_width && get_y < pixmap_height {
-                                    let put_color = blend_rgba(
-                                        [fg_color[0], fg_color[1], fg_color[2], image.data[i]],
-                                        [bg_color[0], bg_color[1], bg_color[2], 255],
-                                    );
-                                    self.rgb_pixmap.put_pixel(get_x, get_y, put_color);
-                                }
+    /// Composites the placed images over the cells just painted.
+    /// Only cells with a repainted background are covered, so every image pixel is blended exactly once.
+    fn paint_images(&mut self, cells: &[CellPaint]) {
+        if self.images.is_empty() {
+            return;
+        }
+        let cell_size = self.physical_cell_size();
+        let clip_end = self.grid_end();
+        let mut images = core::mem::take(&mut self.images);
+        for placement in &mut images {
+            let area = PixelRect {
+                x: self.grid_offset.0 + placement.area.x as usize * cell_size.0,
+                y: self.grid_offset.1 + placement.area.y as usize * cell_size.1,
+                width: placement.area.width as usize * cell_size.0,
+                height: placement.area.height as usize * cell_size.1,
+            };
+            let (width, height) = if placement.stretch {
+                (area.width, area.height)
+            } else {
+                (
+                    math::round(placement.image.pixels.width() as f32 * self.scale_factor) as usize,
+                    math::round(placement.image.pixels.height() as f32 * self.scale_factor) as usize,
+                )
+            };
+            let original = &placement.image;
+            // Only the part inside the placement is scaled, the rest is never shown.
+            let visible = (width.min(area.width), height.min(area.height));
+            let image = if (width, height) == (original.pixels.width(), original.pixels.height()) {
+                original.as_ref()
+            } else {
+                let cached = placement.scaled.as_ref().is_some_and(|(size, scaled)| {
+                    *size == (width, height) && (scaled.pixels.width(), scaled.pixels.height()) == visible
+                });
+                if !cached {
+                    placement.scaled = Some(((width, height), original.scaled(width, height, visible)));
+                }
+                &placement.scaled.as_ref().unwrap().1
+            };
+            let image_width = image.pixels.width();
+
+            let visible_end = ((area.x + visible.0).min(clip_end.0), (area.y + visible.1).min(clip_end.1));
+            for cell in cells.iter().filter(|cell| cell.background.is_some()) {
+                let cell_area = cell.cell_area(cell_size, clip_end);
+                let (left, top) = (cell_area.x.max(area.x), cell_area.y.max(area.y));
+                let right = (cell_area.x + cell_area.width).min(visible_end.0);
+                let bottom = (cell_area.y + cell_area.height).min(visible_end.1);
+                for y in top..bottom {
+                    for x in left..right {
+                        let (image_x, image_y) = (x - area.x, y - area.y);
+                        let color = image.pixels.get_pixel(image_x, image_y);
+                        let color = match &image.alpha {
+                            Some(alpha) => {
+                                blend_coverage(color, self.rgb_pixmap.get_pixel(x, y), alpha[image_y * image_width + image_x])
                             }
+                            None => color,
+                        };
+                        self.rgb_pixmap.put_pixel(x, y, color);
+                    }
+                }
+            }
+        }
+        self.images = images;
+    }
+
+    /// Removes the images covering changed cells and places the images found in their symbols.
+    /// The cells of removed and placed images are added to `changed`, so they get repainted.
+    fn update_images(&mut self, changed: &mut Vec<(u16, u16)>) {
+        let sources: Vec<(u16, u16)> = changed
+            .iter()
+            .copied()
+            .filter(|&(x, y)| has_escapes(self.buffer[(x, y)].symbol()))
+            .collect();
+        if self.images.is_empty() && sources.is_empty() {
+            return;
+        }
 
-                            i += 1;
+        let mut removed = Vec::new();
+        self.images.retain(|placement| {
+            let covered = changed
+                .iter()
+                .any(|&(x, y)| placement.area.contains(Position::new(x, y)));
+            if covered {
+                removed.push(placement.area);
+            }
+            !covered
+        });
+
+        for (x, y) in sources {
+            for command in image_commands(self.buffer[(x, y)].symbol()) {
+                let (image, columns, rows, placeholders) = match command {
+                    ImageCommand::Display {
+                        image,
+                        columns,
+                        rows,
+                        placeholders,
+                    } => (image, columns, rows, placeholders),
+                    ImageCommand::Put {
+                        id,
+                        columns,
+                        rows,
+                        placeholders,
+                    } => match self.kitty_images.get(id) {
+                        Some(image) => (image.clone(), columns, rows, placeholders),
+                        None => continue,
+                    },
+                    ImageCommand::Store { id, image } => {
+                        let placements = &self.images;
+                        let placed = |image: &Arc<InlineImage>| {
+                            placements.iter().any(|placement| Arc::ptr_eq(&placement.image, image))
+                        };
+                        self.kitty_images.insert(id, image, placed);
+                        continue;
+                    }
+                    ImageCommand::Delete { id } => {
+                        let deleted = id.and_then(|id| self.kitty_images.remove(id));
+                        if id.is_none() {
+                            self.kitty_images.clear();
                         }
+                        self.images.retain(|placement| {
+                            let keep = match (id, &deleted) {
+                                (None, _) => false,
+                                (Some(_), Some(deleted)) => !Arc::ptr_eq(deleted, &placement.image),
+                                (Some(_), None) => true,
+                            };
+                            if !keep {
+                                removed.push(placement.area);
+                            }
+                            keep
+                        });
+                        continue;
                     }
-                }
+                };
+                let area = self.image_area(x, y, &image, columns, rows, placeholders);
+                self.place_image(
+                    ImagePlacement {
+                        area,
+                        image,
+                        stretch: columns.is_some() || rows.is_some() || placeholders,
+                        scaled: None,
+                    },
+                    &mut removed,
+                );
             }
         }
+        changed.extend(removed.iter().flat_map(|area| area.positions().map(|position| (position.x, position.y))));
     }
 
-    /// Sets a new font size for the terminal image.
-    /// This will recreate the pixmap and do a full redraw. Do not run every frame.
-    pub fn set_font_size(&mut self, font_size: i32) {
-        let scaled_font_size = font_size as f32 * self.scale_factor;
-        let metrics = Metrics::new(scaled_font_size, scaled_font_size);
-        self.cosmic_buffer
-            .set_metrics(&mut self.font_system, metrics);
-        let mut buffer = CosmicBuffer::new(&mut self.font_system, metrics);
-        let mut buffer = buffer.borrow_with(&mut self.font_system);
-        //"█\n█",
-        buffer.set_text(
-            "█\n█",
-            &Attrs::new().family(Family::Monospace),
-            Shaping::Advanced,
+    /// Adds an image placement and removes the placements it overlaps, like text drawn over their cells would.
+    /// The areas that need a repaint are appended to `repaint`.
+    fn place_image(&mut self, placement: ImagePlacement, repaint: &mut Vec<Rect>) {
+        self.images.retain(|other| {
+            let overlaps = other.area.intersects(placement.area);
+            if overlaps {
+                repaint.push(other.area);
+            }
+            !overlaps
+        });
+        repaint.push(placement.area);
+        self.images.push(placement);
+    }
+
+    /// Returns the cells an image placed at a cell covers. Without a size in cells,
+    /// Kitty placeholders give the size, otherwise the image keeps its pixel size.
+    fn image_area(
+        &self,
+        x: u16,
+        y: u16,
+        image: &InlineImage,
+        columns: Option<u16>,
+        rows: Option<u16>,
+        placeholders: bool,
+    ) -> Rect {
+        let (columns, rows) = if placeholders {
+            // Image widgets write the placeholders of every row into the first cell of the row.
+            let placeholder_rows = (y..self.buffer.area.bottom())
+                .take_while(|&row| has_placeholders(self.buffer[(x, row)].symbol()))
+                .count();
+            (
+                columns.unwrap_or(placeholder_count(self.buffer[(x, y)].symbol()) as u16),
+                rows.unwrap_or(placeholder_rows as u16),
+            )
+        } else {
+            let (char_width, char_height) = (self.char_width.max(1), self.char_height.max(1));
+            (
+                columns.unwrap_or(image.pixels.width().div_ceil(char_width) as u16),
+                rows.unwrap_or(image.pixels.height().div_ceil(char_height) as u16),
+            )
+        };
+        Rect::new(x, y, columns.max(1), rows.max(1)).intersection(self.buffer.area)
+    }
+
+    /// Draws an image stretched over a rectangle of cells, on top of their content.
+    /// The image stays until one of the cells changes or another image overlaps it,
+    /// drawing the same image again only repaints it when it differs.
+    /// Image widgets that write Sixel or Kitty graphics into cell symbols are drawn the same way.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use ratatui::layout::Rect;
+    /// # use soft_ratatui::{RgbPixmap, SoftBackend, Tileset};
+    /// # let tileset = Tileset::from_pixmap(&RgbPixmap::new(128, 128), 8, 8, [255, 0, 255]).unwrap();
+    /// let mut backend = SoftBackend::new_with_tileset(20, 5, tileset, 1.0);
+    /// let mut image = RgbPixmap::new(2, 1);
+    /// image.put_pixel(1, 0, [255, 0, 0]);
+    ///
+    /// backend.draw_image(Rect::new(2, 1, 4, 2), &image);
+    /// assert_eq!(backend.rgb_pixmap.get_pixel(2 * 8, 8), [0, 0, 0]);
+    /// assert_eq!(backend.rgb_pixmap.get_pixel(4 * 8, 8), [255, 0, 0]);
+    /// ```
+    pub fn draw_image(&mut self, area: Rect, image: &RgbPixmap) {
+        let area = area.intersection(self.buffer.area);
+        if area.is_empty() {
+            return;
+        }
+        let unchanged = self.images.iter().any(|placement| {
+            placement.area == area
+                && placement.stretch
+                && placement.image.alpha.is_none()
+                && placement.image.pixels.width() == image.width()
+                && placement.image.pixels.data() == image.data()
+        });
+        if unchanged {
+            return;
+        }
+
+        let mut repaint = Vec::new();
+        self.place_image(
+            ImagePlacement {
+                area,
+                image: Arc::new(InlineImage {
+                    pixels: image.clone(),
+                    alpha: None,
+                }),
+                stretch: true,
+                scaled: None,
+            },
+            &mut repaint,
         );
-        buffer.shape_until_scroll(true);
-        let boop = buffer.layout_runs().next().unwrap();
-        let physical_glyph = boop.glyphs.iter().next().unwrap().physical((0., 0.), self.scale_factor);
-
-        let wa = self
-            .swash_cache
-            .get_image(&mut self.font_system, physical_glyph.cache_key)
-            .clone()
-            .unwrap()
-            .placement;
-        // println!("Glyph height (bbox): {:#?}", wa);
-
-        let char_width = (wa.width as f32 * 0.9) as usize; // Reduce horizontal spacing by 10%
-        let char_height = (wa.height as f32 * 0.85) as usize; // Reduce vertical spacing by 15%
-        self.cosmic_buffer.set_size(
-            &mut self.font_system,
-            Some(char_width as f32 * self.scale_factor),
-            Some(char_height as f32 * self.scale_factor),
+        let changed = repaint.iter().flat_map(|area| area.positions().map(|position| (position.x, position.y)));
+        let cells = self.prepare_update(changed);
+        self.paint_cells(&cells);
+    }
+
+    /// Removes all images, both drawn with [`SoftBackend::draw_image`] and from image protocols.
+    pub fn clear_images(&mut self) {
+        let changed: Vec<(u16, u16)> = self
+            .images
+            .drain(..)
+            .flat_map(|placement| placement.area.positions().map(|position| (position.x, position.y)))
+            .collect();
+        self.kitty_images.clear();
+        let cells = self.prepare_update(changed);
+        self.paint_cells(&cells);
+    }
+
+    /// Sets how glyph edges are smoothed, see [`Antialiasing`].
+    /// Subpixel antialiasing falls back to grayscale where it would produce color fringes,
+    /// see [`SoftBackend::set_output_transformed`] and [`SoftBackend::set_reset_background_transparent`].
+    /// This will do a full redraw. Do not run every frame.
+    pub fn set_antialiasing(&mut self, antialiasing: Antialiasing) {
+        self.antialiasing = antialiasing;
+        self.redraw();
+    }
+
+    /// Tells the backend whether the pixmap is scaled, rotated or mapped onto 3D geometry
+    /// (e.g. used as a bevy texture), which disables subpixel antialiasing.
+    pub fn set_output_transformed(&mut self, transformed: bool) {
+        self.output_transformed = transformed;
+        self.redraw();
+    }
+
+    /// Tells the backend whether the host treats the reset background color as transparent,
+    /// which disables subpixel antialiasing for cells with a reset background.
+    pub fn set_reset_background_transparent(&mut self, transparent: bool) {
+        self.reset_background_transparent = transparent;
+        self.redraw();
+    }
+
+    /// Returns the antialiasing actually used for a cell with the given background.
+    fn cell_antialiasing(&self, rat_bg: Color) -> Antialiasing {
+        match self.antialiasing {
+            Antialiasing::Subpixel(_)
+                if self.output_transformed
+                    || (self.reset_background_transparent && rat_bg == Color::Reset) =>
+            {
+                Antialiasing::Grayscale
+            }
+            antialiasing => antialiasing,
+        }
+    }
+
+    /// Forces all colors to black or white by their luminance, for e-paper and other monochrome displays.
+    /// Text always gets the opposite color of its background, so styles that only differ in hue stay readable.
+    /// Combine with [`RgbPixmap::dither`] to reduce images drawn into the pixmap.
+    /// This will do a full redraw. Do not run every frame.
+    pub fn set_monochrome(&mut self, monochrome: bool) {
+        self.monochrome = monochrome;
+        self.redraw();
+    }
+
+    /// Applies accessibility settings such as disabled blinking, a minimum contrast or a high contrast palette,
+    /// see [`Accessibility`].
+    /// This will do a full redraw. Do not run every frame.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use soft_ratatui::{Accessibility, RgbPixmap, SoftBackend, Tileset};
+    /// # let tileset = Tileset::from_pixmap(&RgbPixmap::new(128, 128), 8, 8, [255, 0, 255]).unwrap();
+    /// let mut backend = SoftBackend::new_with_tileset(20, 5, tileset, 1.0);
+    /// backend.set_accessibility(Accessibility {
+    ///     disable_blinking: true,
+    ///     min_contrast: Some(4.5),
+    ///     ..Accessibility::default()
+    /// });
+    /// assert_eq!(backend.accessibility().min_contrast, Some(4.5));
+    /// ```
+    pub fn set_accessibility(&mut self, accessibility: Accessibility) {
+        self.accessibility = accessibility;
+        self.tick(Duration::ZERO);
+        self.redraw();
+    }
+
+    /// Returns the current accessibility settings.
+    pub fn accessibility(&self) -> Accessibility {
+        self.accessibility
+    }
+
+    /// Resolves a ratatui color, using the accessibility palette when one is set.
+    fn color_rgb(&self, color: &Color, is_a_fg: bool) -> [u8; 3] {
+        palette_rgb(color, is_a_fg, self.accessibility.palette.as_ref())
+    }
+
+    /// Returns the areas of the pixmap changed since the last call, for partial refreshes of e-paper displays.
+    /// Touching areas are merged, and the left and right edges are widened to multiples of `align` pixels,
+    /// e.g. 8 for controllers that address a byte of 1-bit pixels at a time.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use soft_ratatui::{Dithering, GrayDepth, RgbPixmap, SoftBackend, Tileset};
+    /// # let tileset = Tileset::from_pixmap(&RgbPixmap::new(128, 128), 8, 8, [255, 0, 255]).unwrap();
+    /// let mut backend = SoftBackend::new_with_tileset(20, 10, tileset, 1.0);
+    /// backend.set_monochrome(true);
+    /// let frame = backend.rgb_pixmap.dither(GrayDepth::Mono, Dithering::Threshold);
+    /// for rect in backend.take_dirty_rects(8) {
+    ///     let bytes = frame.pack_rect(rect);
+    ///     // send `bytes` to the display window at rect.x, rect.y
+    /// #   assert_eq!(bytes.len(), 20 * 10 * 8);
+    /// }
+    /// assert!(backend.take_dirty_rects(8).is_empty());
+    /// ```
+    pub fn take_dirty_rects(&mut self, align: usize) -> Vec<PixelRect> {
+        self.dirty_rects.take(align, self.rgb_pixmap.width())
+    }
+
+    /// Returns the visible text of the grid line by line, with the rows changed since the previous call
+    /// and the cursor position, so embedders can expose the content to screen readers (e.g. through AccessKit).
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use ratatui::Terminal;
+    /// # use ratatui::widgets::Paragraph;
+    /// # use soft_ratatui::{RgbPixmap, SoftBackend, Tileset};
+    /// # let tileset = Tileset::from_pixmap(&RgbPixmap::new(128, 128), 8, 8, [255, 0, 255]).unwrap();
+    /// let mut terminal = Terminal::new(SoftBackend::new_with_tileset(20, 3, tileset, 1.0)).unwrap();
+    /// terminal.draw(|frame| frame.render_widget(Paragraph::new("Hello\nworld"), frame.area())).unwrap();
+    ///
+    /// let text = terminal.backend_mut().screen_text();
+    /// assert_eq!(text.lines[1].text, "world");
+    /// assert_eq!(text.changed_lines, [0, 1, 2]);
+    /// assert!(terminal.backend_mut().screen_text().changed_lines.is_empty());
+    /// ```
+    pub fn screen_text(&mut self) -> ScreenText {
+        let lines = buffer_lines(&self.buffer);
+        let changed_lines = (0..lines.len() as u16)
+            .filter(|&row| {
+                let previous = self.last_screen_text.as_ref().and_then(|last| last.get(row as usize));
+                previous != Some(&lines[row as usize])
+            })
+            .collect();
+        let cursor = Position::from(self.pos);
+        let cursor = (self.cursor && self.buffer.area.contains(cursor)).then_some(cursor);
+        self.last_screen_text = Some(lines.clone());
+        ScreenText {
+            lines,
+            changed_lines,
+            cursor,
+        }
+    }
+
+    /// Returns the cell at a pixel of the pixmap, e.g. under the mouse, `None` outside of the grid.
+    pub fn cell_at_pixel(&self, x: usize, y: usize) -> Option<Position> {
+        let (cell_width, cell_height) = self.physical_cell_size();
+        let x = x.checked_sub(self.grid_offset.0)? / cell_width.max(1);
+        let y = y.checked_sub(self.grid_offset.1)? / cell_height.max(1);
+        let area = self.buffer.area;
+        (x < area.width as usize && y < area.height as usize).then(|| Position::new(x as u16, y as u16))
+    }
+
+    /// Selects a range of cells, drawn with swapped colors, `None` clears the selection.
+    /// Only the cells entering or leaving the selection are repainted, so it can follow the mouse every frame.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use ratatui::Terminal;
+    /// # use ratatui::layout::Position;
+    /// # use ratatui::widgets::Paragraph;
+    /// # use soft_ratatui::{RgbPixmap, Selection, SelectionMode, SoftBackend, Tileset};
+    /// # let tileset = Tileset::from_pixmap(&RgbPixmap::new(128, 128), 8, 8, [255, 0, 255]).unwrap();
+    /// let mut terminal = Terminal::new(SoftBackend::new_with_tileset(20, 3, tileset, 1.0)).unwrap();
+    /// terminal.draw(|frame| frame.render_widget(Paragraph::new("Hello\nworld"), frame.area())).unwrap();
+    ///
+    /// let backend = terminal.backend_mut();
+    /// // Pressing the mouse button on the first row and releasing it on the second.
+    /// let start = backend.cell_at_pixel(20, 4).unwrap();
+    /// let end = backend.cell_at_pixel(30, 12).unwrap();
+    /// backend.set_selection(Some(Selection::new(start, end, SelectionMode::Linear)));
+    /// assert_eq!(backend.selected_text().unwrap(), "llo\nworl");
+    /// ```
+    pub fn set_selection(&mut self, selection: Option<Selection>) {
+        let old = core::mem::replace(&mut self.selection, selection);
+        if old == selection {
+            return;
+        }
+        let (top, bottom) = [old, selection]
+            .into_iter()
+            .flatten()
+            .map(|selection| selection.rows())
+            .fold((u16::MAX, 0), |(top, bottom), (first, last)| (top.min(first), bottom.max(last)));
+        let mut changed = Vec::new();
+        for y in top..=bottom.min(self.buffer.area.height.saturating_sub(1)) {
+            for x in 0..self.buffer.area.width {
+                let position = Position::new(x, y);
+                if old.is_some_and(|old| old.contains(position))
+                    != selection.is_some_and(|selection| selection.contains(position))
+                {
+                    changed.push((x, y));
+                }
+            }
+        }
+        let cells = self.prepare_update(changed);
+        self.paint_cells(&cells);
+    }
+
+    /// Returns the current selection.
+    pub fn selection(&self) -> Option<Selection> {
+        self.selection
+    }
+
+    /// Returns the target of the OSC 8 hyperlink at a cell, so hosts can open it on click.
+    /// Apps write links as escape sequences into cell symbols, the text of one symbol may cover the following cells.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use ratatui::backend::Backend;
+    /// # use ratatui::buffer::Cell;
+    /// # use ratatui::layout::Position;
+    /// # use soft_ratatui::{RgbPixmap, SoftBackend, Tileset};
+    /// # let tileset = Tileset::from_pixmap(&RgbPixmap::new(128, 128), 8, 8, [255, 0, 255]).unwrap();
+    /// let mut backend = SoftBackend::new_with_tileset(20, 3, tileset, 1.0);
+    /// let link = Cell::new("\x1b]8;;https://ratatui.rs\x07ra\x1b]8;;\x07");
+    /// backend.draw([(0, 0, &link), (1, 0, &Cell::new("a"))].into_iter()).unwrap();
+    ///
+    /// assert_eq!(backend.link_at_cell(Position::new(1, 0)), Some("https://ratatui.rs"));
+    /// assert_eq!(backend.link_at_cell(Position::new(2, 0)), None);
+    /// ```
+    pub fn link_at_cell(&self, position: Position) -> Option<&str> {
+        if !self.buffer.area.contains(position) {
+            return None;
+        }
+        row_links(&self.buffer, position.y)
+            .into_iter()
+            .find(|link| (link.first..=link.last).contains(&position.x))
+            .map(|link| link.url)
+    }
+
+    /// Sets the cell under the mouse, a hyperlink under it is drawn underlined. `None` when the mouse leaves the grid.
+    pub fn set_hovered_cell(&mut self, position: Option<Position>) {
+        self.hovered_cell = position;
+        self.update_hovered_link();
+    }
+
+    /// Finds the hyperlink under the hovered cell again and repaints the link cells that gain or lose the underline.
+    fn update_hovered_link(&mut self) {
+        let area = self.buffer.area;
+        let link = self.hovered_cell.filter(|&position| area.contains(position)).and_then(|position| {
+            row_links(&self.buffer, position.y)
+                .into_iter()
+                .find(|link| (link.first..=link.last).contains(&position.x))
+                .map(|link| (position.y, link.first, link.last.min(area.width - 1)))
+        });
+        let old = core::mem::replace(&mut self.hovered_link, link);
+        if old != link {
+            let changed = [old, link]
+                .into_iter()
+                .flatten()
+                .flat_map(|(row, first, last)| (first..=last).map(move |x| (x, row)));
+            let cells = self.prepare_update(changed);
+            self.paint_cells(&cells);
+        }
+    }
+
+    /// Returns the cells drawn differently from their content: the cursor and the selection.
+    fn overlay_cells(&self) -> Vec<(u16, u16)> {
+        let area = self.buffer.area;
+        let mut cells = Vec::new();
+        if self.cursor {
+            cells.push(self.pos);
+        }
+        if let Some(selection) = self.selection {
+            let (top, bottom) = selection.rows();
+            for row in top..=bottom.min(area.height.saturating_sub(1)) {
+                if let Some((first, last)) = selection.columns(row, area.width) {
+                    cells.extend((first..=last).map(|x| (x, row)));
+                }
+            }
+        }
+        cells
+    }
+
+    /// Selects the word at a cell, the usual response to a double click.
+    /// Outside of words only the cell itself is selected. Returns `None` for cells outside of the grid.
+    pub fn select_word(&mut self, position: Position) -> Option<Selection> {
+        if !self.buffer.area.contains(position) {
+            return None;
+        }
+        let (first, last) = word_columns(&self.buffer, position).unwrap_or((position.x, position.x));
+        let selection = Selection::new(
+            Position::new(first, position.y),
+            Position::new(last, position.y),
+            SelectionMode::Linear,
         );
-        self.char_width = char_width;
-        self.char_height = char_height;
-        let physical_width = (char_width as f32 * self.scale_factor) as usize;
-        let physical_height = (char_height as f32 * self.scale_factor) as usize;
-        self.rgb_pixmap = RgbPixmap::new(
-            physical_width * self.buffer.area.width as usize,
-            physical_height * self.buffer.area.height as usize,
+        self.set_selection(Some(selection));
+        Some(selection)
+    }
+
+    /// Selects a whole row, the usual response to a triple click. Returns `None` for rows outside of the grid.
+    pub fn select_line(&mut self, row: u16) -> Option<Selection> {
+        let area = self.buffer.area;
+        if row >= area.height || area.width == 0 {
+            return None;
+        }
+        let selection = Selection::new(
+            Position::new(0, row),
+            Position::new(area.width - 1, row),
+            SelectionMode::Linear,
         );
+        self.set_selection(Some(selection));
+        Some(selection)
+    }
+
+    /// Returns the text of the selection for copying to the clipboard, one line per selected row.
+    /// Trailing whitespace of every line is trimmed, wide characters are included when any of their cells is selected.
+    pub fn selected_text(&self) -> Option<String> {
+        let selection = self.selection?;
+        let (top, bottom) = selection.rows();
+        let lines: Vec<String> = (top..=bottom.min(self.buffer.area.height.saturating_sub(1)))
+            .filter_map(|row| {
+                let (first, last) = selection.columns(row, self.buffer.area.width)?;
+                Some(row_text(&self.buffer, row, first..=last))
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    /// Sets the color space glyph coverage is blended in, see [`TextBlending`].
+    /// This will do a full redraw. Do not run every frame.
+    pub fn set_text_blending(&mut self, blending: TextBlending) {
+        self.text_blending = blending;
+        self.update_compositor();
+    }
+
+    /// Adjusts how heavy text looks, similar to the font gamma and contrast settings of terminal emulators.
+    /// A gamma above 1.0 thickens strokes and below 1.0 thins them, contrast scales the resulting coverage.
+    /// Both default to 1.0, which leaves the rasterized coverage untouched.
+    /// Values are clamped to 0.1 to 10.0, outside of it text vanishes or turns into solid boxes. NaN keeps 1.0.
+    /// This will do a full redraw. Do not run every frame.
+    pub fn set_text_gamma(&mut self, gamma: f32, contrast: f32) {
+        let valid = |value: f32| if value.is_nan() { 1.0 } else { value.clamp(0.1, 10.0) };
+        self.text_gamma = valid(gamma);
+        self.text_contrast = valid(contrast);
+        self.update_compositor();
+    }
+
+    fn update_compositor(&mut self) {
+        self.compositor = Compositor::new(self.text_blending, self.text_gamma, self.text_contrast);
+        self.cell_cache.clear();
+        self.redraw();
+    }
+
+    /// Sets the memory budget in bytes of the cache of rendered cells, 0 disables caching.
+    /// Cells drawn with a symbol, style and colors seen before are copied from the cache instead of being rasterized again.
+    pub fn set_cell_cache_capacity(&mut self, capacity_bytes: usize) {
+        self.cell_cache.set_capacity(capacity_bytes);
+    }
 
+    /// Sets a new font size in pixels for the terminal image, it also becomes the size restored by [`SoftBackend::reset_zoom`].
+    /// This will recreate the pixmap and do a full redraw. Do not run every frame.
+    pub fn set_font_size(&mut self, font_size: f32) {
+        self.base_font_size = font_size;
+        self.font_size = font_size;
+        self.update_metrics();
+        self.allocate_pixmap();
         self.redraw();
     }
 
+    /// Sets a new font size in typographic points, converted to pixels using the given DPI.
+    /// The scale factor is still applied on top, so pass the logical DPI (96 on most desktops).
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use soft_ratatui::SoftBackend;
+    /// # #[cfg(feature = "cosmic")] {
+    /// # static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+    /// let mut backend = SoftBackend::new_with_font(20, 20, 16.0, FONT_DATA);
+    /// backend.set_font_size_pt(12.0, 96.0);
+    /// assert_eq!(backend.font_size(), 16.0);
+    /// # }
+    /// ```
+    pub fn set_font_size_pt(&mut self, points: f32, dpi: f32) {
+        self.set_font_size(points * dpi / 72.0);
+    }
+
+    /// Returns the current font size in pixels, before scaling.
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// Sets a new scale factor, e.g. when the window moves to a monitor with a different DPI.
+    /// The grid keeps its size in cells while the cells and the pixmap are rescaled.
+    /// This will recreate the pixmap and do a full redraw. Do not run every frame.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+        self.update_metrics();
+        self.allocate_pixmap();
+        self.redraw();
+    }
+
+    /// Sets what zooming keeps stable, see [`ZoomPolicy`].
+    pub fn set_zoom_policy(&mut self, zoom_policy: ZoomPolicy) {
+        self.zoom_policy = zoom_policy;
+    }
+
+    /// Sets by how many pixels [`SoftBackend::zoom_in`] and [`SoftBackend::zoom_out`] change the font size.
+    pub fn set_zoom_step(&mut self, zoom_step: f32) {
+        self.zoom_step = zoom_step;
+    }
+
+    /// Increases the font size by one zoom step, like Ctrl + in a terminal emulator.
+    pub fn zoom_in(&mut self) {
+        self.zoom_to(self.font_size + self.zoom_step);
+    }
+
+    /// Decreases the font size by one zoom step, like Ctrl - in a terminal emulator.
+    pub fn zoom_out(&mut self) {
+        self.zoom_to(self.font_size - self.zoom_step);
+    }
+
+    /// Restores the font size last given to [`SoftBackend::set_font_size`] or the constructor.
+    pub fn reset_zoom(&mut self) {
+        self.zoom_to(self.base_font_size);
+    }
+
+    fn zoom_to(&mut self, font_size: f32) {
+        let font_size = font_size.max(1.0);
+        if font_size == self.font_size {
+            return;
+        }
+        // Remember the pixel area before the cells change size.
+        let window_pixels = self
+            .window_pixels
+            .unwrap_or((self.rgb_pixmap.width(), self.rgb_pixmap.height()));
+        self.font_size = font_size;
+        self.update_metrics();
+        match self.zoom_policy {
+            ZoomPolicy::PreserveGrid => {
+                self.allocate_pixmap();
+                self.redraw();
+            }
+            ZoomPolicy::PreservePixels => {
+                self.resize_to_pixels(window_pixels.0, window_pixels.1);
+            }
+        }
+    }
+
+    /// Re-measures the cell size for the current font size and scale factor.
+    /// Callers are responsible for reallocating and redrawing the pixmap.
+    fn update_metrics(&mut self) {
+        // Rendered cells have the old size and will never be hit again.
+        self.cell_cache.clear();
+        #[cfg(feature = "rayon")]
+        self.workers.clear();
+        let (width, height) = self.renderer.set_size(self.font_size, self.scale_factor);
+        self.physical_cell = (width.max(1), height.max(1));
+        self.char_width = (math::round(self.physical_cell.0 as f32 / self.scale_factor) as usize).max(1);
+        self.char_height = (math::round(self.physical_cell.1 as f32 / self.scale_factor) as usize).max(1);
+    }
+
     /// Creates a new Software Backend with the given font data.
     ///
     /// (new-with-font width height font-size font-data) -> SoftBackend
     ///
     /// * width      : usize - Width of the terminal in cells
     /// * height     : usize - Height of the terminal in cells
-    /// * font-size  : u32   - Font size in pixels
+    /// * font-size  : f32   - Font size in pixels
     /// * font-data  : &[u8] - Byte slice of the font (e.g., included with `include_bytes!`)
     ///
     /// # Examples
     /// ```rust
-    /// static FONT_DATA: &[u8] = include_bytes!("../../assets/iosevka.ttf");
-    /// let backend = SoftBackend::new_with_font(20, 20, 16, FONT_DATA);
+    /// # use soft_ratatui::SoftBackend;
+    /// static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+    /// let backend = SoftBackend::new_with_font(20, 20, 16.0, FONT_DATA);
     /// ```
-
-    pub fn new_with_font(width: u16, height: u16, font_size: i32, font_data: &[u8]) -> Self {
+    #[cfg(feature = "cosmic")]
+    pub fn new_with_font(width: u16, height: u16, font_size: f32, font_data: &[u8]) -> Self {
         Self::new_with_font_and_scale(width, height, font_size, font_data, 1.0)
     }
 
@@ -283,80 +1438,43 @@ impl SoftBackend {
     ///
     /// * width        : u16   - Width of the terminal in cells
     /// * height       : u16   - Height of the terminal in cells
-    /// * font-size    : i32   - Font size in pixels (before scaling)
+    /// * font-size    : f32   - Font size in pixels (before scaling)
     /// * font-data    : &[u8] - Byte slice of the font (e.g., included with `include_bytes!`)
     /// * scale-factor : f32   - Scale factor for high-DPI displays (e.g., 2.0 for retina displays)
     ///
     /// # Examples
     /// ```rust
-    /// static FONT_DATA: &[u8] = include_bytes!("../../assets/iosevka.ttf");
-    /// let backend = SoftBackend::new_with_font_and_scale(20, 20, 16, FONT_DATA, 2.0);
+    /// # use soft_ratatui::SoftBackend;
+    /// static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+    /// let backend = SoftBackend::new_with_font_and_scale(20, 20, 16.0, FONT_DATA, 2.0);
     /// ```
-    pub fn new_with_font_and_scale(width: u16, height: u16, font_size: i32, font_data: &[u8], scale_factor: f32) -> Self {
-        let mut swash_cache = SwashCache::new();
-
-        let mut db = Database::new();
-        // "assets/iosevka.ttf"
-        db.load_font_data(font_data.to_vec());
-        //  db.set_monospace_family("Fira Mono");
-
-        let mut font_system = FontSystem::new_with_locale_and_db("English".to_string(), db);
-        let scaled_font_size = font_size as f32 * scale_factor;
-        let metrics = Metrics::new(scaled_font_size, scaled_font_size);
-
-        let mut buffer = CosmicBuffer::new(&mut font_system, metrics);
-        let mut buffer = buffer.borrow_with(&mut font_system);
-        buffer.set_text(
-            "██",
-            &Attrs::new().family(Family::Monospace),
-            Shaping::Advanced,
-        );
-        buffer.shape_until_scroll(true);
-        let boop = buffer.layout_runs().next().unwrap();
-        let physical_glyph = boop.glyphs.iter().next().unwrap().physical((0., 0.), scale_factor);
-
-        let wa = swash_cache
-            .get_image(&mut font_system, physical_glyph.cache_key)
-            .clone()
-            .unwrap()
-            .placement;
-        // println!("Glyph height (bbox): {:#?}", wa);
-
-        let mut cosmic_buffer = CosmicBuffer::new(&mut font_system, metrics);
-
-        let char_width = (wa.width as f32 * 0.9) as usize; // Reduce horizontal spacing by 10%
-        let char_height = (wa.height as f32 * 0.85) as usize; // Reduce vertical spacing by 15%
-        cosmic_buffer.set_size(
-            &mut font_system,
-            Some(char_width as f32 * scale_factor),
-            Some(char_height as f32 * scale_factor),
-        );
-
-        let physical_width = (char_width as f32 * scale_factor) as usize;
-        let physical_height = (char_height as f32 * scale_factor) as usize;
-        let rgb_pixmap = RgbPixmap::new(physical_width * width as usize, physical_height * height as usize);
-
-        let mut return_struct = Self {
-            buffer: Buffer::empty(Rect::new(0, 0, width, height)),
-            cursor: false,
-            pos: (0, 0),
-            font_system,
-
-            rgb_pixmap,
-            cosmic_buffer,
-            char_width,
-            char_height,
-            scale_factor,
-
-            blink_counter: 0,
-            blinking_fast: false,
-            blinking_slow: false,
-            always_redraw_list: HashSet::new(),
+    #[cfg(feature = "cosmic")]
+    pub fn new_with_font_and_scale(width: u16, height: u16, font_size: f32, font_data: &[u8], scale_factor: f32) -> Self {
+        Self::new_with_renderer(width, height, font_size, CosmicRenderer::from_font_data(font_data), scale_factor)
+    }
 
-            swash_cache,
-        };
-        _ = return_struct.clear();
-        return_struct
+    /// Creates a new Software Backend drawing with the font bundled by the `bundled-font` feature.
+    /// Renders the same pixels on every machine, also on WASM and in CI without any fonts installed.
+    ///
+    /// (new-default width height font-size) -> SoftBackend
+    ///
+    /// * width      : u16 - Width of the terminal in cells
+    /// * height     : u16 - Height of the terminal in cells
+    /// * font-size  : f32 - Font size in pixels
+    ///
+    /// Only ASCII is covered, see [`BUNDLED_FONT`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use soft_ratatui::SoftBackend;
+    /// # #[cfg(feature = "bundled-font")] {
+    /// let backend = SoftBackend::new_default(20, 20, 16.0);
+    /// assert_eq!(backend.char_width, 10);
+    /// # }
+    /// ```
+    #[cfg(feature = "bundled-font")]
+    pub fn new_default(width: u16, height: u16, font_size: f32) -> Self {
+        Self::new_with_font(width, height, font_size, BUNDLED_FONT)
     }
 
     /// Creates a new Software Backend using provided system fonts.
@@ -365,15 +1483,17 @@ impl SoftBackend {
     ///
     /// * width      : usize - Width of the terminal in cells
     /// * height     : usize - Height of the terminal in cells
-    /// * font-size  : u32   - Font size in pixels
+    /// * font-size  : f32   - Font size in pixels
     ///
     /// ⚠️ Not supported on WASM/Web targets.
     ///
     /// # Examples
-    /// ```rust
-    /// let backend = SoftBackend::new_with_system_fonts(20, 20, 16);
+    /// ```rust,no_run
+    /// # use soft_ratatui::SoftBackend;
+    /// let backend = SoftBackend::new_with_system_fonts(20, 20, 16.0);
     /// ```
-    pub fn new_with_system_fonts(width: u16, height: u16, font_size: i32) -> Self {
+    #[cfg(feature = "cosmic")]
+    pub fn new_with_system_fonts(width: u16, height: u16, font_size: f32) -> Self {
         Self::new_with_system_fonts_and_scale(width, height, font_size, 1.0)
     }
 
@@ -383,73 +1503,142 @@ impl SoftBackend {
     ///
     /// * width        : u16   - Width of the terminal in cells
     /// * height       : u16   - Height of the terminal in cells
-    /// * font-size    : i32   - Font size in pixels (before scaling)
+    /// * font-size    : f32   - Font size in pixels (before scaling)
     /// * scale-factor : f32   - Scale factor for high-DPI displays (e.g., 2.0 for retina displays)
     ///
     /// ⚠️ Not supported on WASM/Web targets.
     ///
     /// # Examples
+    /// ```rust,no_run
+    /// # use soft_ratatui::SoftBackend;
+    /// let backend = SoftBackend::new_with_system_fonts_and_scale(20, 20, 16.0, 2.0);
+    /// ```
+    #[cfg(feature = "cosmic")]
+    pub fn new_with_system_fonts_and_scale(width: u16, height: u16, font_size: f32, scale_factor: f32) -> Self {
+        Self::new_with_renderer(width, height, font_size, CosmicRenderer::with_system_fonts(), scale_factor)
+    }
+
+    /// Creates a new Software Backend drawing text with a bitmap font instead of cosmic-text,
+    /// for crisp retro text in pixel-art games.
+    ///
+    /// (new-with-bitmap-font width height font scale-factor) -> SoftBackend
+    ///
+    /// * width        : u16        - Width of the terminal in cells
+    /// * height       : u16        - Height of the terminal in cells
+    /// * font         : BitmapFont - The glyphs, see [`BitmapFont`] for the supported formats
+    /// * scale-factor : f32        - Scale factor, rounded to a whole number of pixels per font pixel
+    ///
+    /// Cells are exactly one glyph in size, so font size and zoom settings have no effect.
+    /// Characters missing from the font are drawn as `?`.
+    ///
+    /// # Examples
     /// ```rust
-    /// let backend = SoftBackend::new_with_system_fonts_and_scale(20, 20, 16, 2.0);
+    /// # use soft_ratatui::{BitmapFont, RgbPixmap, SoftBackend};
+    /// let sheet = RgbPixmap::new(128, 128);
+    /// let font = BitmapFont::from_cp437_sprite_sheet(&sheet).unwrap();
+    /// let backend = SoftBackend::new_with_bitmap_font(20, 20, font, 2.0);
+    /// assert_eq!(backend.get_pixmap_width(), 20 * 8 * 2);
     /// ```
-    pub fn new_with_system_fonts_and_scale(width: u16, height: u16, font_size: i32, scale_factor: f32) -> Self {
-        let mut swash_cache = SwashCache::new();
-
-        let mut font_system = FontSystem::new();
-        let scaled_font_size = font_size as f32 * scale_factor;
-        let metrics = Metrics::new(scaled_font_size, scaled_font_size);
-
-        let mut buffer = CosmicBuffer::new(&mut font_system, metrics);
-        let mut buffer = buffer.borrow_with(&mut font_system);
-        buffer.set_text(
-            "█\n█",
-            &Attrs::new().family(Family::Monospace),
-            Shaping::Advanced,
-        );
-        buffer.shape_until_scroll(true);
-        let boop = buffer.layout_runs().next().unwrap();
-        let physical_glyph = boop.glyphs.iter().next().unwrap().physical((0., 0.), scale_factor);
-
-        let wa = swash_cache
-            .get_image(&mut font_system, physical_glyph.cache_key)
-            .clone()
-            .unwrap()
-            .placement;
-        //  println!("Glyph height (bbox): {:#?}", wa);
-
-        let mut cosmic_buffer = CosmicBuffer::new(&mut font_system, metrics);
-
-        let char_width = (wa.width as f32 * 0.9) as usize; // Reduce horizontal spacing by 10%
-        let char_height = (wa.height as f32 * 0.85) as usize; // Reduce vertical spacing by 15%
-        cosmic_buffer.set_size(
-            &mut font_system,
-            Some(char_width as f32 * scale_factor),
-            Some(char_height as f32 * scale_factor),
-        );
+    pub fn new_with_bitmap_font(width: u16, height: u16, font: BitmapFont, scale_factor: f32) -> Self {
+        Self::new_with_renderer(width, height, font.glyph_height() as f32, font, scale_factor)
+    }
 
-        let physical_width = (char_width as f32 * scale_factor) as usize;
-        let physical_height = (char_height as f32 * scale_factor) as usize;
-        let rgb_pixmap = RgbPixmap::new(physical_width * width as usize, physical_height * height as usize);
+    /// Creates a new Software Backend drawing every cell as a tinted tile, like Dwarf Fortress style roguelikes.
+    ///
+    /// (new-with-tileset width height tileset scale-factor) -> SoftBackend
+    ///
+    /// * width        : u16     - Width of the terminal in cells
+    /// * height       : u16     - Height of the terminal in cells
+    /// * tileset      : Tileset - The tiles and their character mapping
+    /// * scale-factor : f32     - Scale factor, rounded to a whole number of pixels per tile pixel
+    ///
+    /// Cells are exactly one tile in size, so font size and zoom settings have no effect.
+    /// Characters without a tile are drawn with the tile of `?`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use soft_ratatui::{RgbPixmap, SoftBackend, Tileset};
+    /// let sheet = RgbPixmap::new(16 * 12, 16 * 12);
+    /// let tileset = Tileset::from_pixmap(&sheet, 12, 12, [255, 0, 255]).unwrap();
+    /// let backend = SoftBackend::new_with_tileset(20, 20, tileset, 1.0);
+    /// assert_eq!(backend.get_pixmap_width(), 20 * 12);
+    /// ```
+    pub fn new_with_tileset(width: u16, height: u16, tileset: Tileset, scale_factor: f32) -> Self {
+        Self::new_with_renderer(width, height, tileset.tile_height() as f32, tileset, scale_factor)
+    }
 
+    /// Creates a new Software Backend drawing text with any [`GlyphRenderer`].
+    ///
+    /// (new-with-renderer width height font-size renderer scale-factor) -> SoftBackend
+    ///
+    /// * width        : u16           - Width of the terminal in cells
+    /// * height       : u16           - Height of the terminal in cells
+    /// * font-size    : f32           - Font size in pixels (before scaling), passed on to the renderer
+    /// * renderer     : GlyphRenderer - Rasterizes the text of the cells
+    /// * scale-factor : f32           - Scale factor for high-DPI displays (e.g., 2.0 for retina displays)
+    ///
+    /// See [`GlyphRenderer`] for an example.
+    pub fn new_with_renderer(
+        width: u16,
+        height: u16,
+        font_size: f32,
+        renderer: impl GlyphRenderer + 'static,
+        scale_factor: f32,
+    ) -> Self {
         let mut return_struct = Self {
             buffer: Buffer::empty(Rect::new(0, 0, width, height)),
             cursor: false,
             pos: (0, 0),
-            font_system,
-
-            rgb_pixmap,
-            cosmic_buffer,
-            char_width,
-            char_height,
+            renderer: Box::new(renderer),
+
+            rgb_pixmap: RgbPixmap::new(0, 0),
+            font_size,
+            base_font_size: font_size,
+            zoom_step: 1.0,
+            zoom_policy: ZoomPolicy::PreserveGrid,
+            char_width: 0,
+            char_height: 0,
+            physical_cell: (0, 0),
             scale_factor,
 
-            blink_counter: 0,
             blinking_fast: false,
             blinking_slow: false,
-            always_redraw_list: HashSet::new(),
-
-            swash_cache,
+            blink_clock: Duration::ZERO,
+            slow_blink: BlinkTiming::SLOW,
+            rapid_blink: BlinkTiming::RAPID,
+            cursor_blink: Some(BlinkTiming::CURSOR),
+            cursor_blink_start: Duration::ZERO,
+            cursor_on: true,
+            blinking_cells: HashSet::new(),
+            overflowing_cells: HashSet::new(),
+            letterbox: None,
+            window_pixels: None,
+            grid_offset: (0, 0),
+            monochrome: false,
+            dirty_rects: DirtyRects::default(),
+            accessibility: Accessibility::default(),
+            last_screen_text: None,
+            selection: None,
+            hovered_cell: None,
+            hovered_link: None,
+            images: Vec::new(),
+            kitty_images: StoredImages::default(),
+
+            cell_cache: CellCache::new(DEFAULT_CELL_CACHE_BYTES),
+            #[cfg(feature = "rayon")]
+            workers: Vec::new(),
+            #[cfg(feature = "rayon")]
+            prerendered: HashMap::new(),
+            compositor: Compositor::default(),
+            antialiasing: Antialiasing::default(),
+            output_transformed: false,
+            reset_background_transparent: false,
+            text_blending: TextBlending::default(),
+            text_gamma: 1.0,
+            text_contrast: 1.0,
         };
+        return_struct.update_metrics();
+        return_struct.allocate_pixmap();
         _ = return_struct.clear();
         return_struct
     }
@@ -461,51 +1650,226 @@ impl SoftBackend {
 
     /// Resizes the `SoftBackend` to the specified width and height.
     pub fn resize(&mut self, width: u16, height: u16) {
+        self.window_pixels = None;
+        self.resize_grid(width, height);
+    }
+
+    /// Resizes the `SoftBackend` to the largest grid that fits into the given pixel area,
+    /// using the current cell metrics and scale factor.
+    ///
+    /// Without a letterbox the pixmap shrinks to the grid and the leftover pixels are only reported.
+    /// With a letterbox (see [`SoftBackend::set_letterbox`]) the pixmap covers the whole area and
+    /// the margins are filled with the padding color.
+    ///
+    /// * width-px  : usize - Width of the area in physical pixels
+    /// * height-px : usize - Height of the area in physical pixels
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use soft_ratatui::SoftBackend;
+    /// # #[cfg(feature = "cosmic")] {
+    /// # static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+    /// let mut backend = SoftBackend::new_with_font(20, 20, 16.0, FONT_DATA);
+    /// let fit = backend.resize_to_pixels(800, 600);
+    /// assert_eq!(backend.buffer().area.width, fit.columns);
+    /// # }
+    /// ```
+    pub fn resize_to_pixels(&mut self, width_px: usize, height_px: usize) -> PixelFit {
+        let (physical_char_width, physical_char_height) = self.physical_cell_size();
+        let columns = (width_px / physical_char_width.max(1)).clamp(1, u16::MAX as usize);
+        let rows = (height_px / physical_char_height.max(1)).clamp(1, u16::MAX as usize);
+
+        self.window_pixels = Some((width_px, height_px));
+        self.resize_grid(columns as u16, rows as u16);
+
+        PixelFit {
+            columns: columns as u16,
+            rows: rows as u16,
+            margin_x: width_px.saturating_sub(columns * physical_char_width),
+            margin_y: height_px.saturating_sub(rows * physical_char_height),
+        }
+    }
+
+    /// Sets how the grid is placed inside the area given to [`SoftBackend::resize_to_pixels`].
+    /// `None` disables letterboxing, so the pixmap always matches the grid exactly.
+    pub fn set_letterbox(&mut self, letterbox: Option<Letterbox>) {
+        self.letterbox = letterbox;
+        self.allocate_pixmap();
+        self.redraw();
+    }
+
+    fn resize_grid(&mut self, width: u16, height: u16) {
         self.buffer.resize(Rect::new(0, 0, width, height));
-        let physical_width = (self.char_width as f32 * self.scale_factor) as usize;
-        let physical_height = (self.char_height as f32 * self.scale_factor) as usize;
-        let rgb_pixmap = RgbPixmap::new(
-            physical_width * width as usize,
-            physical_height * height as usize,
-        );
-        self.rgb_pixmap = rgb_pixmap;
+        self.hovered_link = None;
+        self.images.clear();
+        self.allocate_pixmap();
         self.redraw();
+        self.update_hovered_link();
+    }
+
+    /// Recreates the pixmap for the current grid, cell metrics and letterbox settings.
+    fn allocate_pixmap(&mut self) {
+        let (physical_char_width, physical_char_height) = self.physical_cell_size();
+        let grid_width = physical_char_width * self.buffer.area.width as usize;
+        let grid_height = physical_char_height * self.buffer.area.height as usize;
+
+        // The whole pixmap is new, earlier changes no longer matter.
+        self.dirty_rects = DirtyRects::default();
+        match (self.letterbox, self.window_pixels) {
+            (Some(letterbox), Some((window_width, window_height))) => {
+                let width = window_width.max(grid_width);
+                let height = window_height.max(grid_height);
+                self.grid_offset = if letterbox.centered {
+                    ((width - grid_width) / 2, (height - grid_height) / 2)
+                } else {
+                    (0, 0)
+                };
+                self.rgb_pixmap = RgbPixmap::new(width, height);
+                self.rgb_pixmap.fill(letterbox.padding_color);
+            }
+            _ => {
+                self.grid_offset = (0, 0);
+                self.rgb_pixmap = RgbPixmap::new(grid_width, grid_height);
+            }
+        }
+        self.dirty_rects.add(PixelRect {
+            x: 0,
+            y: 0,
+            width: self.rgb_pixmap.width(),
+            height: self.rgb_pixmap.height(),
+        });
     }
 
     /// Redraws the pixmap
     pub fn redraw(&mut self) {
-        self.always_redraw_list = HashSet::new();
-        
-        // First pass: draw all backgrounds
+        self.blinking_cells = HashSet::new();
+        self.overflowing_cells = HashSet::new();
+
+        let mut cells = Vec::new();
         for x in 0..self.buffer.area.width {
             for y in 0..self.buffer.area.height {
-                self.draw_cell_background(x, y);
+                cells.extend(self.prepare_cell(x, y));
             }
         }
-        
-        // Second pass: draw all text (allows overflow)
-        for x in 0..self.buffer.area.width {
-            for y in 0..self.buffer.area.height {
-                self.draw_cell_text(x, y);
+        self.paint_cells(&cells);
+    }
+
+    /// Advances the blink clock by `delta` and repaints blinking text and the cursor if their phase changed.
+    /// Call it with the time since the last call, e.g. once per frame, blinking only moves forward through this clock.
+    /// Returns `true` if the pixmap changed and has to be presented again.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use core::time::Duration;
+    /// # use soft_ratatui::{RgbPixmap, SoftBackend, Tileset};
+    /// # let tileset = Tileset::from_pixmap(&RgbPixmap::new(128, 128), 8, 8, [255, 0, 255]).unwrap();
+    /// let mut backend = SoftBackend::new_with_tileset(20, 5, tileset, 1.0);
+    /// // Without blinking text or a cursor there is nothing to wake up for.
+    /// assert_eq!(backend.next_blink(), None);
+    /// assert!(!backend.tick(Duration::from_millis(16)));
+    /// ```
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.blink_clock += delta;
+
+        let blinks = !self.accessibility.disable_blinking;
+        let slow_hidden = blinks && !self.slow_blink.is_visible(self.blink_clock);
+        let fast_hidden = blinks && !self.rapid_blink.is_visible(self.blink_clock);
+        let mut changed_modifiers = Modifier::empty();
+        changed_modifiers.set(Modifier::SLOW_BLINK, slow_hidden != self.blinking_slow);
+        changed_modifiers.set(Modifier::RAPID_BLINK, fast_hidden != self.blinking_fast);
+        self.blinking_slow = slow_hidden;
+        self.blinking_fast = fast_hidden;
+
+        let mut repaint: Vec<(u16, u16)> = Vec::new();
+        if !changed_modifiers.is_empty() {
+            repaint.extend(self.blinking_cells.iter().copied().filter(|&(x, y)| {
+                self.buffer
+                    .cell(Position::new(x, y))
+                    .is_some_and(|cell| cell.modifier.intersects(changed_modifiers))
+            }));
+        }
+
+        let cursor_on = self.cursor_blink_visible();
+        if cursor_on != self.cursor_on {
+            self.cursor_on = cursor_on;
+            if self.cursor {
+                repaint.push(self.pos);
+            }
+        }
+
+        if repaint.is_empty() {
+            return false;
+        }
+        let cells = self.prepare_update(repaint);
+        self.paint_cells(&cells);
+        true
+    }
+
+    /// Returns the time until blinking text or the cursor changes next, `None` if nothing on screen blinks.
+    /// Lets event driven applications sleep until the next call of [`SoftBackend::tick`] is due.
+    pub fn next_blink(&self) -> Option<Duration> {
+        if self.accessibility.disable_blinking {
+            return None;
+        }
+        let mut blinking = Modifier::empty();
+        for &(x, y) in &self.blinking_cells {
+            if let Some(cell) = self.buffer.cell(Position::new(x, y)) {
+                blinking |= cell.modifier & (Modifier::SLOW_BLINK | Modifier::RAPID_BLINK);
             }
         }
+        let slow = blinking
+            .contains(Modifier::SLOW_BLINK)
+            .then(|| self.slow_blink.next_change(self.blink_clock))
+            .flatten();
+        let rapid = blinking
+            .contains(Modifier::RAPID_BLINK)
+            .then(|| self.rapid_blink.next_change(self.blink_clock))
+            .flatten();
+        let cursor = self
+            .cursor_blink
+            .filter(|_| self.cursor && self.buffer.area.contains(self.pos.into()))
+            .and_then(|timing| timing.next_change(self.blink_clock - self.cursor_blink_start));
+        [slow, rapid, cursor].into_iter().flatten().min()
     }
 
-    fn update_blinking(&mut self) {
-        self.blink_counter = (self.blink_counter + 1) % 200;
+    /// Sets how fast text with the `SLOW_BLINK` and `RAPID_BLINK` modifiers blinks,
+    /// defaults to [`BlinkTiming::SLOW`] and [`BlinkTiming::RAPID`].
+    pub fn set_blink_timing(&mut self, slow: BlinkTiming, rapid: BlinkTiming) {
+        self.slow_blink = slow;
+        self.rapid_blink = rapid;
+        self.tick(Duration::ZERO);
+    }
 
-        self.blinking_fast = matches!(self.blink_counter % 100, 0..=5);
-        self.blinking_slow = matches!(self.blink_counter, 20..=25);
+    /// Sets how the cursor blinks, `None` keeps it steady. Defaults to [`BlinkTiming::CURSOR`].
+    pub fn set_cursor_blink(&mut self, timing: Option<BlinkTiming>) {
+        self.cursor_blink = timing;
+        self.tick(Duration::ZERO);
+    }
+
+    fn cursor_blink_visible(&self) -> bool {
+        self.accessibility.disable_blinking
+            || self
+                .cursor_blink
+                .is_none_or(|timing| timing.is_visible(self.blink_clock - self.cursor_blink_start))
+    }
+
+    /// Restarts the cursor blink cycle in its visible phase and repaints the given cells,
+    /// so a moved or newly shown cursor is always visible right away.
+    fn restart_cursor_blink(&mut self, cells: impl IntoIterator<Item = (u16, u16)>) {
+        self.cursor_blink_start = self.blink_clock;
+        self.cursor_on = true;
+        let cells = self.prepare_update(cells);
+        self.paint_cells(&cells);
     }
 }
 
 impl Backend for SoftBackend {
-    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    type Error = BackendError;
+
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), Self::Error>
     where
         I: Iterator<Item = (u16, u16, &'a Cell)>,
     {
-        self.update_blinking();
-        
         // Collect all cells that need updating
         let mut cells_to_update: Vec<(u16, u16)> = Vec::new();
         
@@ -513,60 +1877,104 @@ impl Backend for SoftBackend {
             self.buffer[(x, y)] = c.clone();
             cells_to_update.push((x, y));
         }
-        
-        // Add blinking cells
-        for (x, y) in self.always_redraw_list.clone().iter() {
-            cells_to_update.push((*x, *y));
-        }
-        
-        // First pass: draw backgrounds
-        for (x, y) in &cells_to_update {
-            self.draw_cell_background(*x, *y);
-        }
-        
-        // Second pass: draw text (allows overflow)
-        for (x, y) in &cells_to_update {
-            self.draw_cell_text(*x, *y);
-        }
+        self.update_images(&mut cells_to_update);
+
+        let cells = self.prepare_update(cells_to_update);
+        self.paint_cells(&cells);
+        self.update_hovered_link();
 
         Ok(())
     }
 
-    fn hide_cursor(&mut self) -> io::Result<()> {
-        self.cursor = false;
-
+    fn hide_cursor(&mut self) -> Result<(), Self::Error> {
+        if self.cursor {
+            self.cursor = false;
+            let cells = self.prepare_update([self.pos]);
+            self.paint_cells(&cells);
+        }
         Ok(())
     }
 
-    fn show_cursor(&mut self) -> io::Result<()> {
-        self.cursor = true;
+    fn show_cursor(&mut self) -> Result<(), Self::Error> {
+        if !self.cursor {
+            self.cursor = true;
+            self.restart_cursor_blink([self.pos]);
+        }
         Ok(())
     }
 
-    fn get_cursor_position(&mut self) -> io::Result<Position> {
+    fn get_cursor_position(&mut self) -> Result<Position, Self::Error> {
         Ok(self.pos.into())
     }
 
-    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> Result<(), Self::Error> {
+        let old = self.pos;
         self.pos = position.into().into();
+        if self.cursor && self.pos != old {
+            self.restart_cursor_blink([old, self.pos]);
+        }
         Ok(())
     }
 
-    fn clear(&mut self) -> io::Result<()> {
+    fn clear(&mut self) -> Result<(), Self::Error> {
         self.buffer.reset();
         let clear_cell = Cell::EMPTY;
-        let colorik = rat_to_rgb(&clear_cell.bg, false);
+        let colorik = self.color_rgb(&clear_cell.bg, false);
+
+        let (grid_end_x, grid_end_y) = self.grid_end();
+        let (offset_x, offset_y) = self.grid_offset;
+        let grid = PixelRect {
+            x: offset_x,
+            y: offset_y,
+            width: grid_end_x - offset_x,
+            height: grid_end_y - offset_y,
+        };
+        self.rgb_pixmap.fill_rect(grid.x, grid.y, grid.width, grid.height, [colorik[0], colorik[1], colorik[2]]);
+        self.dirty_rects.add(grid);
+        self.hovered_link = None;
+        self.images.clear();
+        let cells = self.prepare_update(self.overlay_cells());
+        self.paint_cells(&cells);
+
+        Ok(())
+    }
 
-        self.rgb_pixmap.fill([colorik[0], colorik[1], colorik[2]]);
+    fn clear_region(&mut self, clear_type: ClearType) -> Result<(), Self::Error> {
+        let area = self.buffer.area;
+        if area.is_empty() {
+            return Ok(());
+        }
+        let cursor = self.buffer.index_of(
+            self.pos.0.min(area.width - 1),
+            self.pos.1.min(area.height - 1),
+        );
+        let line_start = cursor - cursor % area.width as usize;
+        let line_end = line_start + area.width as usize;
+        let region = match clear_type {
+            ClearType::All => return self.clear(),
+            ClearType::AfterCursor => cursor..self.buffer.content.len(),
+            ClearType::BeforeCursor => 0..cursor + 1,
+            ClearType::CurrentLine => line_start..line_end,
+            ClearType::UntilNewLine => cursor..line_end,
+        };
 
+        let mut changed = Vec::with_capacity(region.len());
+        for index in region {
+            self.buffer.content[index].reset();
+            changed.push(self.buffer.pos_of(index));
+        }
+        self.update_images(&mut changed);
+        let cells = self.prepare_update(changed);
+        self.paint_cells(&cells);
+        self.update_hovered_link();
         Ok(())
     }
 
-    fn size(&self) -> io::Result<Size> {
+    fn size(&self) -> Result<Size, Self::Error> {
         Ok(self.buffer.area.as_size())
     }
 
-    fn window_size(&mut self) -> io::Result<WindowSize> {
+    fn window_size(&mut self) -> Result<WindowSize, Self::Error> {
         let window_pixels = Size {
             width: self.get_pixmap_width() as u16,
             height: self.get_pixmap_height() as u16,
@@ -577,7 +1985,7 @@ impl Backend for SoftBackend {
         })
     }
 
-    fn flush(&mut self) -> io::Result<()> {
+    fn flush(&mut self) -> Result<(), Self::Error> {
         Ok(())
     }
 }
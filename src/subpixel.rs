@@ -0,0 +1,88 @@
+#[cfg(feature = "cosmic")]
+use crate::collections::HashMap;
+
+#[cfg(feature = "cosmic")]
+use cosmic_text::{CacheKey, CacheKeyFlags, FontSystem, SwashImage};
+#[cfg(feature = "cosmic")]
+use swash::scale::{Render, ScaleContext, Source, StrikeWith};
+#[cfg(feature = "cosmic")]
+use swash::zeno::{Angle, Format, Transform, Vector};
+
+/// How glyph edges are smoothed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Antialiasing {
+    /// One coverage value per pixel, works on any output.
+    #[default]
+    Grayscale,
+    /// One coverage value per color channel, sharper on LCD panels with the given subpixel layout.
+    /// Only looks right when the pixmap is shown unscaled on an opaque background.
+    Subpixel(SubpixelOrder),
+    /// No smoothing, every pixel is either foreground or background, for crisp pixel-art text.
+    None,
+}
+
+/// Physical order of the color stripes inside an LCD pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubpixelOrder {
+    Rgb,
+    Bgr,
+}
+
+/// Rasterizes glyphs with per-channel coverage, using the same sources and hinting as cosmic-text's `SwashCache`.
+#[cfg(feature = "cosmic")]
+pub struct SubpixelCache {
+    context: ScaleContext,
+    images: HashMap<(CacheKey, SubpixelOrder), Option<SwashImage>>,
+}
+
+#[cfg(feature = "cosmic")]
+impl SubpixelCache {
+    pub fn new() -> Self {
+        Self {
+            context: ScaleContext::new(),
+            images: HashMap::new(),
+        }
+    }
+
+    /// Returns the subpixel mask of a glyph, color glyphs such as emoji keep their color content.
+    pub fn get_image(
+        &mut self,
+        font_system: &mut FontSystem,
+        cache_key: CacheKey,
+        order: SubpixelOrder,
+    ) -> &Option<SwashImage> {
+        let context = &mut self.context;
+        self.images.entry((cache_key, order)).or_insert_with(|| {
+            let font = font_system.get_font(cache_key.font_id)?;
+            let mut scaler = context
+                .builder(font.as_swash())
+                .size(f32::from_bits(cache_key.font_size_bits))
+                .hint(true)
+                .build();
+            let offset = Vector::new(cache_key.x_bin.as_float(), cache_key.y_bin.as_float());
+            let format = match order {
+                SubpixelOrder::Rgb => Format::Subpixel,
+                SubpixelOrder::Bgr => Format::subpixel_bgra(),
+            };
+            Render::new(&[
+                Source::ColorOutline(0),
+                Source::ColorBitmap(StrikeWith::BestFit),
+                Source::Outline,
+            ])
+            .format(format)
+            .offset(offset)
+            .transform(
+                cache_key
+                    .flags
+                    .contains(CacheKeyFlags::FAKE_ITALIC)
+                    .then(|| Transform::skew(Angle::from_degrees(14.0), Angle::from_degrees(0.0))),
+            )
+            .render(&mut scaler, cache_key.glyph_id)
+        })
+    }
+
+    /// Drops all masks, needed whenever the font size changes.
+    pub fn clear(&mut self) {
+        self.images.clear();
+    }
+}
@@ -0,0 +1,227 @@
+//! Pixel snapshot tests for widgets, enabled by the `testing` feature.
+//!
+//! [`assert_render_snapshot!`](crate::assert_render_snapshot) compares the pixmap of a backend with a golden image
+//! stored in `tests/snapshots` of the crate under test. Snapshots are only written with
+//! `SOFT_RATATUI_UPDATE_SNAPSHOTS=1`, to create new ones or accept an intended change. Without it a missing snapshot
+//! fails the assertion, so a checkout without committed snapshots cannot pass by accident.
+//!
+//! On a mismatch or a missing snapshot the test fails and leaves files next to the golden image:
+//! `<name>.actual.ppm` with the rendered pixels, `<name>.diff.ppm` marking differing pixels in red,
+//! and `<name>.actual.txt` with the text of the buffer, to compare with the golden `<name>.txt`.
+//!
+//! Snapshots are plain binary PPM images, most image viewers open them.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::path::Path;
+use std::{env, fs, io};
+
+use ratatui_core::buffer::Buffer;
+
+use crate::pixmap::RgbPixmap;
+use crate::soft_backend::SoftBackend;
+
+/// Environment variable that makes snapshot assertions write the golden files instead of comparing.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "SOFT_RATATUI_UPDATE_SNAPSHOTS";
+
+/// Creates a backend drawing with the [`BUNDLED_FONT`](crate::BUNDLED_FONT) at 16 pixels and a scale factor of 1,
+/// so snapshots do not depend on the fonts installed on a machine.
+pub fn test_backend(width: u16, height: u16) -> SoftBackend {
+    SoftBackend::new_default(width, height, 16.0)
+}
+
+/// How far a rendering may stray from its snapshot and still pass, see [`PixmapDiff`](crate::PixmapDiff).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Largest difference of a color channel that still counts as the same pixel.
+    pub channel: u8,
+    /// Number of pixels allowed to differ by more than `channel`.
+    pub pixels: usize,
+    /// Lets renderings with more differing pixels pass as long as their structural similarity
+    /// reaches this value, e.g. 0.98 to accept antialiasing changes of a font rasterizer update.
+    pub min_ssim: Option<f32>,
+}
+
+impl Default for Tolerance {
+    /// Forgives slight antialiasing differences, but no differing pixels.
+    fn default() -> Self {
+        Self {
+            channel: 2,
+            pixels: 0,
+            min_ssim: None,
+        }
+    }
+}
+
+/// Asserts that the pixmap of a backend matches a stored snapshot.
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "testing")] {
+/// use ratatui::Terminal;
+/// use ratatui::widgets::{Block, Paragraph};
+/// use soft_ratatui::assert_render_snapshot;
+/// use soft_ratatui::testing::{Tolerance, test_backend};
+///
+/// let mut terminal = Terminal::new(test_backend(20, 3)).unwrap();
+/// terminal
+///     .draw(|frame| frame.render_widget(Paragraph::new("Hello").block(Block::bordered()), frame.area()))
+///     .unwrap();
+/// // Compares with tests/snapshots/hello_block.ppm
+/// assert_render_snapshot!(terminal.backend(), "hello_block");
+/// // A custom tolerance is given as third argument.
+/// let tolerance = Tolerance { min_ssim: Some(0.98), ..Tolerance::default() };
+/// assert_render_snapshot!(terminal.backend(), "hello_block", tolerance);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_render_snapshot {
+    ($backend:expr, $name:expr $(,)?) => {
+        $crate::assert_render_snapshot!($backend, $name, $crate::testing::Tolerance::default())
+    };
+    ($backend:expr, $name:expr, $tolerance:expr $(,)?) => {{
+        let backend: &$crate::SoftBackend = &$backend;
+        $crate::testing::assert_snapshot(
+            backend,
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots"),
+            $name,
+            $tolerance,
+        )
+    }};
+}
+
+/// Compares the pixmap of a backend with the snapshot `<dir>/<name>.ppm`, see the [module docs](self).
+/// Usually called through [`assert_render_snapshot!`](crate::assert_render_snapshot).
+///
+/// # Panics
+/// When the snapshot is missing, the pixmap differs from it by more than `tolerance`, or files can not be read or written.
+#[track_caller]
+pub fn assert_snapshot(backend: &SoftBackend, dir: &Path, name: &str, tolerance: Tolerance) {
+    let expected_path = dir.join(format!("{name}.ppm"));
+    let actual_path = dir.join(format!("{name}.actual.ppm"));
+    let diff_path = dir.join(format!("{name}.diff.ppm"));
+    let text_path = dir.join(format!("{name}.txt"));
+    let actual_text_path = dir.join(format!("{name}.actual.txt"));
+
+    let actual = &backend.rgb_pixmap;
+    let actual_text = buffer_text(backend.buffer());
+    let update = env::var_os(UPDATE_SNAPSHOTS_VAR).is_some_and(|value| !value.is_empty() && value != "0");
+
+    if update {
+        fs::create_dir_all(dir).expect("failed to create the snapshot directory");
+        fs::write(&expected_path, to_ppm(actual)).expect("failed to write the snapshot");
+        fs::write(&text_path, &actual_text).expect("failed to write the snapshot");
+        for stale in [&actual_path, &diff_path, &actual_text_path] {
+            _ = fs::remove_file(stale);
+        }
+        return;
+    }
+
+    let expected = match fs::read(&expected_path) {
+        Ok(bytes) => {
+            Some(from_ppm(&bytes).unwrap_or_else(|| panic!("snapshot {} is not a binary PPM image", expected_path.display())))
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => panic!("failed to read the snapshot {}: {error}", expected_path.display()),
+    };
+
+    _ = fs::remove_file(&diff_path);
+    let failure = match expected {
+        Some(expected) => compare(&expected, actual, tolerance, &diff_path),
+        None => Some(format!("{} is missing", expected_path.display())),
+    };
+
+    match failure {
+        None => {
+            for stale in [&actual_path, &actual_text_path] {
+                _ = fs::remove_file(stale);
+            }
+        }
+        Some(reason) => {
+            fs::create_dir_all(dir).expect("failed to create the snapshot directory");
+            fs::write(&actual_path, to_ppm(actual)).expect("failed to write the actual image");
+            fs::write(&actual_text_path, &actual_text).expect("failed to write the actual buffer");
+            let expected_text = fs::read_to_string(&text_path).unwrap_or_default();
+            panic!(
+                "snapshot {name} failed: {reason}\n\
+                 expected buffer:\n{expected_text}\n\
+                 actual buffer:\n{actual_text}\n\
+                 actual image written to {}, set {UPDATE_SNAPSHOTS_VAR}=1 to accept it",
+                actual_path.display()
+            );
+        }
+    }
+}
+
+/// Returns why `actual` does not match `expected` within `tolerance`, `None` if it does.
+/// Writes the diff image to `diff_path` when pixels differ.
+fn compare(expected: &RgbPixmap, actual: &RgbPixmap, tolerance: Tolerance, diff_path: &Path) -> Option<String> {
+    let diff = expected.diff(actual);
+    if !diff.same_size() {
+        return Some(format!(
+            "size {}x{} differs from the snapshot size {}x{}",
+            actual.width(),
+            actual.height(),
+            expected.width(),
+            expected.height()
+        ));
+    }
+    let differing = diff.count_over(tolerance.channel);
+    let similar = || tolerance.min_ssim.is_some_and(|min_ssim| diff.ssim() >= min_ssim);
+    if differing <= tolerance.pixels || similar() {
+        return None;
+    }
+    fs::write(diff_path, to_ppm(&diff.to_image(tolerance.channel))).expect("failed to write the diff image");
+    let bounds = diff
+        .bounds(tolerance.channel)
+        .map(|rect| format!(" within {}x{} at ({}, {})", rect.width, rect.height, rect.x, rect.y))
+        .unwrap_or_default();
+    Some(format!(
+        "{differing} pixels differ by up to {}{bounds}, {} allowed, SSIM {:.4}",
+        diff.max_delta(),
+        tolerance.pixels,
+        diff.ssim()
+    ))
+}
+
+/// Returns the symbols of the buffer, one line per row.
+fn buffer_text(buffer: &Buffer) -> String {
+    let mut text = String::new();
+    for row in buffer.content.chunks(buffer.area.width.max(1) as usize) {
+        for cell in row {
+            text.push_str(cell.symbol());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Encodes a pixmap as a binary PPM (P6) image, the format of snapshots.
+pub fn to_ppm(pixmap: &RgbPixmap) -> Vec<u8> {
+    let mut ppm = format!("P6\n{} {}\n255\n", pixmap.width(), pixmap.height()).into_bytes();
+    ppm.extend_from_slice(pixmap.data());
+    ppm
+}
+
+/// Decodes a binary PPM (P6) image with 8 bits per channel, as written by [`to_ppm`].
+/// Returns `None` for other formats and malformed data.
+pub fn from_ppm(bytes: &[u8]) -> Option<RgbPixmap> {
+    // The header is four whitespace separated fields, comments are not supported.
+    let mut fields = Vec::new();
+    let mut rest = bytes;
+    while fields.len() < 4 {
+        let start = rest.iter().position(|b| !b.is_ascii_whitespace())?;
+        let len = rest[start..].iter().position(|b| b.is_ascii_whitespace())?;
+        fields.push(core::str::from_utf8(&rest[start..start + len]).ok()?);
+        // Exactly one whitespace byte separates the header from the pixels.
+        rest = &rest[start + len + 1..];
+    }
+    let width: usize = fields[1].parse().ok()?;
+    let height: usize = fields[2].parse().ok()?;
+    if fields[0] != "P6" || fields[3] != "255" || rest.len() != 3 * width * height {
+        return None;
+    }
+    let mut pixmap = RgbPixmap::new(width, height);
+    pixmap.data_mut().copy_from_slice(rest);
+    Some(pixmap)
+}
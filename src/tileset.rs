@@ -0,0 +1,199 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::collections::HashMap;
+use crate::cp437::CP437;
+use crate::glyph_renderer::{CellText, GlyphContent, GlyphImage, GlyphRenderer};
+use crate::math;
+use crate::pixmap::{RgbPixmap, upscale};
+
+/// A sheet of equally sized tiles, e.g. a Dwarf Fortress style tileset, drawn instead of font glyphs.
+///
+/// Tiles are tinted when drawn: white becomes the foreground color of the cell, other colors are multiplied with it,
+/// and transparent pixels show the background color. Characters are mapped to tiles in code page 437 order
+/// unless [`Tileset::with_chars`] assigns them differently.
+///
+/// # Examples
+/// ```rust
+/// # use soft_ratatui::{RgbPixmap, Tileset};
+/// let sheet = RgbPixmap::new(16 * 10, 16 * 10);
+/// // Magenta pixels show the background color.
+/// let tileset = Tileset::from_pixmap(&sheet, 10, 10, [255, 0, 255]).unwrap();
+/// assert_eq!(tileset.tile_width(), 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tileset {
+    tile_width: usize,
+    tile_height: usize,
+    /// RGBA pixels of every tile, in sheet order.
+    tiles: Vec<Vec<u8>>,
+    mapping: HashMap<char, usize>,
+    /// Whole number of pixels per tile pixel, derived from the scale factor.
+    scale: usize,
+}
+
+/// Why a tileset could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TilesetError {
+    /// The tile size is zero or does not divide the sheet.
+    InvalidTileSize,
+    /// The pixel data does not match the size of the sheet.
+    InvalidSheetSize,
+}
+
+impl fmt::Display for TilesetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TilesetError::InvalidTileSize => write!(f, "tile size does not divide the sheet"),
+            TilesetError::InvalidSheetSize => write!(f, "pixel data does not match the sheet size"),
+        }
+    }
+}
+
+impl core::error::Error for TilesetError {}
+
+impl Tileset {
+    /// Cuts an RGBA image into tiles, pixels with an alpha of 0 are transparent.
+    /// Partially transparent pixels are blended over the background.
+    ///
+    /// * data        : &[u8] - RGBA pixels of the sheet, row by row
+    /// * width       : usize - Width of the sheet in pixels
+    /// * height      : usize - Height of the sheet in pixels
+    /// * tile-width  : usize - Width of a tile in pixels
+    /// * tile-height : usize - Height of a tile in pixels
+    pub fn from_rgba(
+        data: &[u8],
+        width: usize,
+        height: usize,
+        tile_width: usize,
+        tile_height: usize,
+    ) -> Result<Self, TilesetError> {
+        if tile_width == 0
+            || tile_height == 0
+            || !width.is_multiple_of(tile_width)
+            || !height.is_multiple_of(tile_height)
+        {
+            return Err(TilesetError::InvalidTileSize);
+        }
+        if width.checked_mul(height).and_then(|pixels| pixels.checked_mul(4)) != Some(data.len()) {
+            return Err(TilesetError::InvalidSheetSize);
+        }
+
+        let mut tiles = Vec::new();
+        for top in (0..height).step_by(tile_height) {
+            for left in (0..width).step_by(tile_width) {
+                let mut tile = Vec::with_capacity(4 * tile_width * tile_height);
+                for y in top..top + tile_height {
+                    let start = 4 * (y * width + left);
+                    tile.extend_from_slice(&data[start..start + 4 * tile_width]);
+                }
+                tiles.push(tile);
+            }
+        }
+
+        Ok(Self {
+            tile_width,
+            tile_height,
+            tiles,
+            mapping: HashMap::new(),
+            scale: 1,
+        }
+        .with_chars(CP437))
+    }
+
+    /// Cuts an RGB image into tiles, pixels of the key color are transparent.
+    pub fn from_pixmap(
+        sheet: &RgbPixmap,
+        tile_width: usize,
+        tile_height: usize,
+        key_color: [u8; 3],
+    ) -> Result<Self, TilesetError> {
+        let mut rgba = Vec::with_capacity(4 * sheet.width() * sheet.height());
+        for pixel in sheet.data().chunks_exact(3) {
+            let alpha = if pixel == key_color { 0 } else { 255 };
+            rgba.extend_from_slice(&[pixel[0], pixel[1], pixel[2], alpha]);
+        }
+        Self::from_rgba(&rgba, sheet.width(), sheet.height(), tile_width, tile_height)
+    }
+
+    /// Assigns characters to the tiles in sheet order, replacing the previous mapping.
+    /// When a character appears more than once, its first tile is used.
+    pub fn with_chars(mut self, chars: impl IntoIterator<Item = char>) -> Self {
+        self.mapping.clear();
+        for (index, c) in chars.into_iter().take(self.tiles.len()).enumerate() {
+            self.mapping.entry(c).or_insert(index);
+        }
+        self
+    }
+
+    /// Assigns a single character to the tile at `index` in sheet order, e.g. to give a sprite its own character.
+    pub fn map_char(&mut self, c: char, index: usize) {
+        if index < self.tiles.len() {
+            self.mapping.insert(c, index);
+        }
+    }
+
+    /// Returns the width of a tile in pixels, before scaling.
+    pub fn tile_width(&self) -> usize {
+        self.tile_width
+    }
+
+    /// Returns the height of a tile in pixels, before scaling.
+    pub fn tile_height(&self) -> usize {
+        self.tile_height
+    }
+}
+
+impl GlyphRenderer for Tileset {
+    /// Tiles only scale by whole pixels to stay crisp, the font size is ignored.
+    fn set_size(&mut self, _font_size: f32, scale_factor: f32) -> (usize, usize) {
+        self.scale = (math::round(scale_factor) as usize).max(1);
+        (self.tile_width * self.scale, self.tile_height * self.scale)
+    }
+
+    /// Tints the tile of the cell with the foreground color, transparent pixels are left to the background.
+    /// Underline and strikeout combining marks become lines in the foreground color.
+    fn render(&mut self, text: &CellText) -> Vec<GlyphImage> {
+        let (width, height) = (self.tile_width, self.tile_height);
+        let fg = text.fg;
+        let opaque_fg = [fg[0], fg[1], fg[2], 255].repeat(width);
+        let mut pixels = vec![0; 4 * width * height];
+
+        let base = text.symbol.chars().find(|c| !matches!(c, '\u{0332}' | '\u{0336}'));
+        if let Some(tile) = base.and_then(|c| self.mapping.get(&c).or_else(|| self.mapping.get(&'?'))) {
+            for (pixel, rgba) in pixels.chunks_exact_mut(4).zip(self.tiles[*tile].chunks_exact(4)) {
+                pixel.copy_from_slice(&[
+                    (rgba[0] as u32 * fg[0] as u32 / 255) as u8,
+                    (rgba[1] as u32 * fg[1] as u32 / 255) as u8,
+                    (rgba[2] as u32 * fg[2] as u32 / 255) as u8,
+                    rgba[3],
+                ]);
+            }
+        }
+        if text.symbol.contains('\u{0332}') {
+            pixels[4 * (height - 1) * width..].copy_from_slice(&opaque_fg);
+        }
+        if text.symbol.contains('\u{0336}') {
+            let middle = 4 * (height / 2) * width;
+            pixels[middle..middle + 4 * width].copy_from_slice(&opaque_fg);
+        }
+        if pixels.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+            return Vec::new();
+        }
+
+        vec![GlyphImage {
+            left: 0,
+            top: 0,
+            width: width * self.scale,
+            height: height * self.scale,
+            content: GlyphContent::Color,
+            data: upscale(&pixels, width, 4, self.scale),
+        }]
+    }
+
+    fn fork(&self) -> Option<Box<dyn GlyphRenderer>> {
+        Some(Box::new(self.clone()))
+    }
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{CELL, backend, cell_is, draw_cell, pixel};
+use ratatui::backend::Backend;
+use ratatui::style::{Color, Modifier, Style};
+use soft_ratatui::Accessibility;
+
+/// WCAG 2 contrast ratio, computed independently of the backend.
+fn contrast(a: [u8; 3], b: [u8; 3]) -> f32 {
+    let luminance = |color: [u8; 3]| {
+        let linear = |channel: u8| {
+            let c = channel as f32 / 255.0;
+            if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        };
+        0.2126 * linear(color[0]) + 0.7152 * linear(color[1]) + 0.0722 * linear(color[2])
+    };
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+#[test]
+fn disabled_blinking_keeps_text_and_cursor_steady() {
+    let mut backend = backend(4, 2);
+    backend.set_accessibility(Accessibility {
+        disable_blinking: true,
+        ..Accessibility::default()
+    });
+    let style = Style::new()
+        .fg(Color::White)
+        .bg(Color::Black)
+        .add_modifier(Modifier::RAPID_BLINK | Modifier::SLOW_BLINK);
+    draw_cell(&mut backend, 0, 0, "#", style);
+    backend.set_cursor_position((1, 0)).unwrap();
+    backend.show_cursor().unwrap();
+
+    assert_eq!(backend.next_blink(), None);
+    for _ in 0..100 {
+        assert!(!backend.tick(Duration::from_millis(50)));
+        assert!(cell_is(&backend, 0, 0, [255, 255, 255]));
+        assert!(cell_is(&backend, 1, 0, [215, 215, 215]));
+    }
+}
+
+#[test]
+fn disabling_blinking_shows_hidden_text() {
+    let mut backend = backend(4, 2);
+    let style = Style::new()
+        .fg(Color::White)
+        .bg(Color::Black)
+        .add_modifier(Modifier::SLOW_BLINK);
+    draw_cell(&mut backend, 0, 0, "#", style);
+    backend.tick(Duration::from_millis(600));
+    assert!(cell_is(&backend, 0, 0, [0, 0, 0]));
+
+    backend.set_accessibility(Accessibility {
+        disable_blinking: true,
+        ..Accessibility::default()
+    });
+    assert!(cell_is(&backend, 0, 0, [255, 255, 255]));
+}
+
+#[test]
+fn min_contrast_adjusts_low_contrast_text() {
+    let mut backend = backend(4, 2);
+    backend.set_accessibility(Accessibility {
+        min_contrast: Some(4.5),
+        ..Accessibility::default()
+    });
+    draw_cell(&mut backend, 0, 0, "#", Style::new().fg(Color::DarkGray).bg(Color::Black));
+    let fg = pixel(&backend, 0, 0, 0, 0);
+    assert!(contrast(fg, [0, 0, 0]) >= 4.5);
+    assert!(contrast(fg, [0, 0, 0]) < 5.0, "moved further than needed: {fg:?}");
+
+    // Dark text on a light background is darkened instead.
+    draw_cell(&mut backend, 1, 0, "#", Style::new().fg(Color::Gray).bg(Color::White));
+    let fg = pixel(&backend, 1, 0, 0, 0);
+    assert!(fg[0] < 128 && contrast(fg, [255, 255, 255]) >= 4.5);
+}
+
+#[test]
+fn min_contrast_keeps_readable_and_hidden_text() {
+    let mut backend = backend(4, 2);
+    backend.set_accessibility(Accessibility {
+        min_contrast: Some(7.0),
+        ..Accessibility::default()
+    });
+    draw_cell(&mut backend, 0, 0, "#", Style::new().fg(Color::White).bg(Color::Blue));
+    assert!(cell_is(&backend, 0, 0, [255, 255, 255]));
+
+    let hidden = Style::new()
+        .fg(Color::White)
+        .bg(Color::Blue)
+        .add_modifier(Modifier::HIDDEN);
+    draw_cell(&mut backend, 1, 0, "#", hidden);
+    assert!(cell_is(&backend, 1, 0, [0, 0, 139]));
+}
+
+#[test]
+fn palette_overrides_named_and_reset_colors() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, "#", Style::new().fg(Color::Red));
+    draw_cell(&mut backend, 1, 0, " ", Style::new().bg(Color::Indexed(12)));
+    draw_cell(&mut backend, 2, 0, " ", Style::new().bg(Color::Rgb(1, 2, 3)));
+    backend.set_accessibility(Accessibility {
+        palette: Some(Accessibility::HIGH_CONTRAST_PALETTE),
+        ..Accessibility::default()
+    });
+
+    assert!(cell_is(&backend, 0, 0, [255, 96, 96]));
+    assert!(cell_is(&backend, 1, 0, [176, 208, 255]));
+    assert!(cell_is(&backend, 2, 0, [1, 2, 3]));
+    assert!(cell_is(&backend, 3, 1, [0, 0, 0]));
+
+    // Cleared cells use the palette too.
+    backend.clear().unwrap();
+    assert_eq!(backend.rgb_pixmap.get_pixel(CELL, 0), [0, 0, 0]);
+}
+
+#[test]
+fn high_contrast_preset() {
+    let mut backend = backend(4, 2);
+    backend.set_accessibility(Accessibility::high_contrast());
+    draw_cell(&mut backend, 0, 0, "#", Style::new().fg(Color::White).bg(Color::Blue));
+    let fg = pixel(&backend, 0, 0, 0, 0);
+    assert!(contrast(fg, [128, 160, 255]) >= 7.0);
+    assert_eq!(backend.accessibility(), Accessibility::high_contrast());
+}
@@ -0,0 +1,65 @@
+//! Aliased text and bitmap fonts draw every pixel in either the foreground or the background color.
+
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::style::{Color, Style};
+use soft_ratatui::{BitmapFont, SoftBackend};
+
+#[cfg(feature = "cosmic")]
+static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+
+const FG: [u8; 3] = [255, 200, 0];
+const BG: [u8; 3] = [0, 0, 90];
+
+const BDF: &str = "\
+STARTFONT 2.1
+FONTBOUNDINGBOX 8 8 0 0
+STARTCHAR A
+ENCODING 65
+BBX 8 8 0 0
+BITMAP
+18
+24
+42
+7E
+42
+42
+42
+00
+ENDCHAR
+ENDFONT
+";
+
+fn draw(backend: &mut SoftBackend, symbol: &'static str) -> Vec<[u8; 3]> {
+    let mut cell = Cell::new(symbol);
+    cell.set_style(Style::new().fg(Color::Rgb(FG[0], FG[1], FG[2])).bg(Color::Rgb(BG[0], BG[1], BG[2])));
+    backend.draw([(0, 0, &cell)].into_iter()).unwrap();
+    backend.get_pixmap_data().chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect()
+}
+
+#[test]
+#[cfg(feature = "cosmic")]
+fn aliased_text_has_no_blended_pixels() {
+    use soft_ratatui::Antialiasing;
+
+    let mut backend = SoftBackend::new_with_font(1, 1, 16.0, FONT_DATA);
+    backend.set_antialiasing(Antialiasing::None);
+    let pixels = draw(&mut backend, "H");
+    assert!(pixels.iter().all(|pixel| *pixel == FG || *pixel == BG));
+    assert!(pixels.contains(&FG));
+    assert!(pixels.contains(&BG));
+}
+
+#[test]
+fn bitmap_glyphs_scale_by_whole_pixels() {
+    let font = BitmapFont::from_bdf(BDF).unwrap();
+    let glyph = font.glyph('A').unwrap().to_vec();
+    let mut backend = SoftBackend::new_with_bitmap_font(1, 1, font, 2.0);
+    assert_eq!((backend.get_pixmap_width(), backend.get_pixmap_height()), (16, 16));
+    let pixels = draw(&mut backend, "A");
+    for (index, pixel) in pixels.iter().enumerate() {
+        let (x, y) = (index % 16, index / 16);
+        let expected = if glyph[y / 2 * 8 + x / 2] > 0 { FG } else { BG };
+        assert_eq!(*pixel, expected, "({x}, {y})");
+    }
+}
@@ -0,0 +1,139 @@
+use soft_ratatui::{BitmapFont, BitmapFontError};
+
+const BDF: &str = "\
+STARTFONT 2.1
+FONTBOUNDINGBOX 8 8 0 0
+STARTCHAR A
+ENCODING 65
+BBX 8 8 0 0
+BITMAP
+18
+24
+42
+7E
+42
+42
+42
+00
+ENDCHAR
+ENDFONT
+";
+
+fn bdf_with(from: &str, to: &str) -> Result<BitmapFont, BitmapFontError> {
+    assert!(BDF.contains(from));
+    BitmapFont::from_bdf(&BDF.replace(from, to))
+}
+
+/// A PSF1 font with 256 glyphs of 8x`height`, glyph `A` has its top row set.
+fn psf1(height: u8) -> Vec<u8> {
+    let mut data = vec![0x36, 0x04, 0x00, height];
+    data.resize(4 + 256 * height as usize, 0);
+    if height > 0 {
+        data[4 + 65 * height as usize] = 0xFF;
+    }
+    data
+}
+
+/// A PSF2 header followed by `bitmap_bytes` zero bytes.
+fn psf2(count: u32, glyph_bytes: u32, height: u32, width: u32, bitmap_bytes: usize) -> Vec<u8> {
+    let mut data = vec![0x72, 0xb5, 0x4a, 0x86];
+    for field in [0, 32, 0, count, glyph_bytes, height, width] {
+        data.extend_from_slice(&u32::to_le_bytes(field));
+    }
+    data.resize(32 + bitmap_bytes, 0);
+    data
+}
+
+#[test]
+fn bdf_glyphs_load() {
+    let font = BitmapFont::from_bdf(BDF).unwrap();
+    assert_eq!((font.glyph_width(), font.glyph_height()), (8, 8));
+    let glyph = font.glyph('A').unwrap();
+    assert_eq!(&glyph[..8], &[0, 0, 0, 255, 255, 0, 0, 0]);
+}
+
+#[test]
+fn malformed_bdf_is_rejected() {
+    assert_eq!(
+        bdf_with("FONTBOUNDINGBOX 8 8 0 0\n", "").unwrap_err(),
+        BitmapFontError::InvalidBdf("BITMAP".into())
+    );
+    assert_eq!(
+        BitmapFont::from_bdf("STARTFONT 2.1\nENDFONT\n").unwrap_err(),
+        BitmapFontError::UnknownFormat
+    );
+    assert!(matches!(
+        bdf_with("ENCODING 65", "ENCODING sixty-five"),
+        Err(BitmapFontError::InvalidBdf(_))
+    ));
+    assert!(matches!(bdf_with("7E\n", "7G\n"), Err(BitmapFontError::InvalidBdf(_))));
+    assert_eq!(
+        BitmapFont::from_bdf(&BDF[..BDF.find("42").unwrap()]).unwrap_err(),
+        BitmapFontError::Truncated
+    );
+}
+
+#[test]
+fn oversized_bdf_bounding_boxes_are_rejected() {
+    for bounding_box in ["2147483647 2147483647 0 0", "100000 8 0 0", "0 8 0 0", "-8 8 0 0"] {
+        assert_eq!(
+            bdf_with("FONTBOUNDINGBOX 8 8 0 0", &format!("FONTBOUNDINGBOX {bounding_box}")).unwrap_err(),
+            BitmapFontError::InvalidGlyphSize,
+            "{bounding_box}"
+        );
+    }
+}
+
+#[test]
+fn extreme_bdf_glyph_offsets_do_not_overflow() {
+    // Offsets far outside of the font box draw nothing instead of overflowing.
+    let font = bdf_with("BBX 8 8 0 0", "BBX 8 8 2147483647 -2147483648").unwrap();
+    assert!(font.glyph('A').unwrap().iter().all(|&coverage| coverage == 0));
+    // A glyph claiming more rows than it has stops at the first line that is not a bitmap row.
+    assert_eq!(
+        bdf_with("BBX 8 8 0 0", "BBX 8 2147483647 0 0").unwrap_err(),
+        BitmapFontError::InvalidBdf("ENDCHAR".into())
+    );
+}
+
+#[test]
+fn psf1_glyphs_load() {
+    let font = BitmapFont::from_psf(&psf1(16)).unwrap();
+    assert_eq!((font.glyph_width(), font.glyph_height()), (8, 16));
+    assert!(font.glyph('A').unwrap()[..8].iter().all(|&coverage| coverage == 255));
+}
+
+#[test]
+fn malformed_psf1_is_rejected() {
+    assert_eq!(BitmapFont::from_psf(&psf1(0)).unwrap_err(), BitmapFontError::InvalidGlyphSize);
+    let data = psf1(16);
+    assert_eq!(BitmapFont::from_psf(&data[..data.len() - 1]).unwrap_err(), BitmapFontError::Truncated);
+    assert_eq!(BitmapFont::from_psf(&data[..3]).unwrap_err(), BitmapFontError::UnknownFormat);
+}
+
+#[test]
+fn psf2_glyphs_load() {
+    let font = BitmapFont::from_psf(&psf2(256, 32, 16, 12, 256 * 32)).unwrap();
+    assert_eq!((font.glyph_width(), font.glyph_height()), (12, 16));
+}
+
+#[test]
+fn malformed_psf2_is_rejected() {
+    // Header fields whose product overflows or points past the data.
+    assert_eq!(
+        BitmapFont::from_psf(&psf2(u32::MAX, u32::MAX, 16, 8, 64)).unwrap_err(),
+        BitmapFontError::Truncated
+    );
+    assert_eq!(BitmapFont::from_psf(&psf2(256, 16, 16, 8, 255 * 16)).unwrap_err(), BitmapFontError::Truncated);
+    assert_eq!(BitmapFont::from_psf(&psf2(256, 16, 16, 8, 0)[..20]).unwrap_err(), BitmapFontError::Truncated);
+
+    // Glyph sizes that are empty, huge or larger than a glyph's bytes.
+    let sizes = [(16, 0, 8), (16, 16, 0), (u32::MAX, u32::MAX, u32::MAX), (u32::MAX, 1, 100_000), (15, 16, 8)];
+    for (glyph_bytes, height, width) in sizes {
+        assert_eq!(
+            BitmapFont::from_psf(&psf2(1, glyph_bytes, height, width, 64)).unwrap_err(),
+            BitmapFontError::InvalidGlyphSize,
+            "{width}x{height} in {glyph_bytes} bytes"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Glyph coverage is blended from the background to the foreground color with rounded integer math.
+#![cfg(feature = "cosmic")]
+
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::style::{Color, Style};
+use soft_ratatui::{SoftBackend, TextBlending};
+
+static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+
+const FG: [u8; 3] = [200, 100, 0];
+const BG: [u8; 3] = [0, 50, 250];
+
+fn draw(symbol: &str) -> SoftBackend {
+    draw_with(symbol, FG, BG, |_| {})
+}
+
+fn draw_with(symbol: &str, fg: [u8; 3], bg: [u8; 3], setup: impl FnOnce(&mut SoftBackend)) -> SoftBackend {
+    let mut backend = SoftBackend::new_with_font(1, 1, 16.0, FONT_DATA);
+    setup(&mut backend);
+    let mut cell = Cell::default();
+    cell.set_symbol(symbol);
+    cell.set_style(Style::new().fg(Color::Rgb(fg[0], fg[1], fg[2])).bg(Color::Rgb(bg[0], bg[1], bg[2])));
+    backend.draw([(0, 0, &cell)].into_iter()).unwrap();
+    backend
+}
+
+/// Draws a white "H" on black, so every channel of a pixel is its blended coverage.
+fn white_on_black(setup: impl FnOnce(&mut SoftBackend)) -> Vec<u8> {
+    draw_with("H", [255; 3], [0; 3], setup).get_pixmap_data().to_vec()
+}
+
+/// The color of a pixel with the given coverage, rounded to the nearest integer.
+fn blend(coverage: u32) -> [u8; 3] {
+    let channel = |fg: u8, bg: u8| ((fg as u32 * coverage + bg as u32 * (255 - coverage) + 127) / 255) as u8;
+    [channel(FG[0], BG[0]), channel(FG[1], BG[1]), channel(FG[2], BG[2])]
+}
+
+#[test]
+fn glyph_pixels_blend_between_background_and_foreground() {
+    let backend = draw("H");
+    let pixels: Vec<[u8; 3]> = backend.get_pixmap_data().chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect();
+    let coverages: Vec<u32> = pixels
+        .iter()
+        .map(|pixel| (0..=255).find(|&coverage| blend(coverage) == *pixel).expect("pixel is not a blend"))
+        .collect();
+    // Background, solid strokes in the exact foreground color and antialiased edges.
+    assert!(coverages.contains(&0));
+    assert!(coverages.contains(&255));
+    assert!(coverages.iter().any(|&coverage| coverage > 0 && coverage < 255));
+}
+
+#[test]
+fn linear_blending_brightens_light_text_edges() {
+    let srgb = white_on_black(|_| {});
+    let linear = white_on_black(|backend| backend.set_text_blending(TextBlending::Linear));
+    // Same background and solid strokes, only the antialiased edges are lighter in linear light.
+    for (srgb, linear) in srgb.iter().zip(&linear) {
+        assert!(linear >= srgb);
+        assert_eq!(*srgb == 0, *linear == 0);
+        assert_eq!(*srgb == 255, *linear == 255);
+    }
+    assert!(srgb.iter().zip(&linear).any(|(srgb, linear)| linear > srgb));
+}
+
+#[test]
+fn text_gamma_changes_stroke_weight() {
+    let weight = |pixels: Vec<u8>| pixels.iter().map(|&value| value as u32).sum::<u32>();
+    let default = white_on_black(|_| {});
+    assert_eq!(white_on_black(|backend| backend.set_text_gamma(1.0, 1.0)), default);
+    let default = weight(default);
+    assert!(weight(white_on_black(|backend| backend.set_text_gamma(2.0, 1.0))) > default);
+    assert!(weight(white_on_black(|backend| backend.set_text_gamma(0.5, 1.0))) < default);
+    assert!(weight(white_on_black(|backend| backend.set_text_gamma(1.0, 0.5))) < default);
+}
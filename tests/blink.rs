@@ -0,0 +1,138 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{backend, cell_is, draw_cell};
+use ratatui::backend::Backend;
+use ratatui::style::{Color, Modifier, Style};
+use soft_ratatui::BlinkTiming;
+
+const WHITE: [u8; 3] = [255, 255, 255];
+const BLACK: [u8; 3] = [0, 0, 0];
+const RESET_BG: [u8; 3] = [24, 24, 24];
+const RESET_FG: [u8; 3] = [215, 215, 215];
+
+fn blinking(modifier: Modifier) -> Style {
+    Style::new()
+        .fg(Color::White)
+        .bg(Color::Black)
+        .add_modifier(modifier)
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn rapid_blink_follows_the_clock() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, "#", blinking(Modifier::RAPID_BLINK));
+    assert!(cell_is(&backend, 0, 0, WHITE));
+
+    assert!(!backend.tick(ms(100)));
+    assert!(cell_is(&backend, 0, 0, WHITE));
+    assert!(backend.tick(ms(100)));
+    assert!(cell_is(&backend, 0, 0, BLACK));
+    assert!(backend.tick(ms(200)));
+    assert!(cell_is(&backend, 0, 0, WHITE));
+}
+
+#[test]
+fn slow_blink_is_visible_half_the_time() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, "#", blinking(Modifier::SLOW_BLINK));
+
+    let mut hidden_ticks = 0;
+    let mut changes = 0;
+    for _ in 0..200 {
+        if backend.tick(ms(10)) {
+            changes += 1;
+        }
+        if cell_is(&backend, 0, 0, BLACK) {
+            hidden_ticks += 1;
+        }
+    }
+    assert_eq!(hidden_ticks, 100);
+    assert_eq!(changes, 4);
+}
+
+#[test]
+fn draws_do_not_advance_blinking() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, "#", blinking(Modifier::RAPID_BLINK));
+    for _ in 0..100 {
+        backend.draw(std::iter::empty()).unwrap();
+    }
+    assert!(cell_is(&backend, 0, 0, WHITE));
+}
+
+#[test]
+fn next_blink_reports_the_next_phase_change() {
+    let mut backend = backend(4, 2);
+    assert_eq!(backend.next_blink(), None);
+
+    draw_cell(&mut backend, 0, 0, "#", blinking(Modifier::SLOW_BLINK));
+    assert_eq!(backend.next_blink(), Some(ms(500)));
+    backend.tick(ms(200));
+    assert_eq!(backend.next_blink(), Some(ms(300)));
+
+    // The blinking cell is overwritten, nothing blinks anymore.
+    draw_cell(&mut backend, 0, 0, "#", blinking(Modifier::empty()));
+    assert_eq!(backend.next_blink(), None);
+}
+
+#[test]
+fn blink_timing_is_configurable() {
+    let mut backend = backend(4, 2);
+    backend.set_blink_timing(BlinkTiming::new(ms(100), 0.25), BlinkTiming::RAPID);
+    draw_cell(&mut backend, 0, 0, "#", blinking(Modifier::SLOW_BLINK));
+    assert_eq!(backend.next_blink(), Some(ms(25)));
+
+    backend.tick(ms(30));
+    assert!(cell_is(&backend, 0, 0, BLACK));
+    backend.tick(ms(70));
+    assert!(cell_is(&backend, 0, 0, WHITE));
+}
+
+#[test]
+fn cursor_is_drawn_and_blinks() {
+    let mut backend = backend(4, 2);
+    backend.set_cursor_position((1, 0)).unwrap();
+    assert!(cell_is(&backend, 1, 0, RESET_BG));
+
+    backend.show_cursor().unwrap();
+    assert!(cell_is(&backend, 1, 0, RESET_FG));
+    assert_eq!(backend.next_blink(), Some(ms(600)));
+
+    assert!(backend.tick(ms(600)));
+    assert!(cell_is(&backend, 1, 0, RESET_BG));
+    assert!(backend.tick(ms(600)));
+    assert!(cell_is(&backend, 1, 0, RESET_FG));
+
+    backend.hide_cursor().unwrap();
+    assert!(cell_is(&backend, 1, 0, RESET_BG));
+    assert_eq!(backend.next_blink(), None);
+}
+
+#[test]
+fn moving_the_cursor_restarts_its_blink() {
+    let mut backend = backend(4, 2);
+    backend.show_cursor().unwrap();
+    backend.tick(ms(700));
+    assert!(cell_is(&backend, 0, 0, RESET_BG));
+
+    backend.set_cursor_position((2, 1)).unwrap();
+    assert!(cell_is(&backend, 0, 0, RESET_BG));
+    assert!(cell_is(&backend, 2, 1, RESET_FG));
+    assert_eq!(backend.next_blink(), Some(ms(600)));
+}
+
+#[test]
+fn steady_cursor_never_blinks() {
+    let mut backend = backend(4, 2);
+    backend.set_cursor_blink(None);
+    backend.show_cursor().unwrap();
+    assert_eq!(backend.next_blink(), None);
+    assert!(!backend.tick(ms(5000)));
+    assert!(cell_is(&backend, 0, 0, RESET_FG));
+}
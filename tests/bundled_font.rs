@@ -0,0 +1,21 @@
+//! The bundled font draws text without any fonts installed on the machine.
+#![cfg(feature = "bundled-font")]
+
+use ratatui::Terminal;
+use ratatui::widgets::Paragraph;
+use soft_ratatui::{BUNDLED_FONT, SoftBackend};
+
+fn draw(backend: SoftBackend) -> Vec<u8> {
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(|frame| frame.render_widget(Paragraph::new("Hello"), frame.area())).unwrap();
+    terminal.backend().get_pixmap_data().to_vec()
+}
+
+#[test]
+fn default_backend_draws_with_the_bundled_font() {
+    let bundled = draw(SoftBackend::new_default(8, 1, 16.0));
+    assert_eq!(bundled, draw(SoftBackend::new_with_font(8, 1, 16.0, BUNDLED_FONT)));
+    // Text pixels differ from the reset background.
+    let background = &bundled[bundled.len() - 3..];
+    assert!(bundled.chunks_exact(3).any(|pixel| pixel != background));
+}
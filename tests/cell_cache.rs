@@ -0,0 +1,46 @@
+//! Cells drawn again are copied from the cache of rendered cells, they have to look like freshly rendered ones.
+#![cfg(feature = "cosmic")]
+
+use ratatui::Terminal;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Paragraph};
+use soft_ratatui::SoftBackend;
+
+static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+
+fn draw(backend: SoftBackend, text: &str) -> Terminal<SoftBackend> {
+    let mut terminal = Terminal::new(backend).unwrap();
+    let style = Style::new()
+        .fg(Color::LightGreen)
+        .bg(Color::Rgb(0, 0, 80))
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new(text).style(style).block(Block::bordered()), frame.area()))
+        .unwrap();
+    terminal
+}
+
+#[test]
+fn cached_cells_match_a_cold_render() {
+    let text = "Hello hello, wow";
+    let mut cached = draw(SoftBackend::new_with_font(20, 4, 16.0, FONT_DATA), text);
+    // Every cell of the redraw is copied from the cache.
+    cached.backend_mut().redraw();
+
+    let mut uncached = SoftBackend::new_with_font(20, 4, 16.0, FONT_DATA);
+    uncached.set_cell_cache_capacity(0);
+    let uncached = draw(uncached, text);
+    assert_eq!(cached.backend().get_pixmap_data(), uncached.backend().get_pixmap_data());
+}
+
+#[test]
+fn a_small_cache_still_renders_every_cell() {
+    let mut small = SoftBackend::new_with_font(20, 4, 16.0, FONT_DATA);
+    // Room for a few cells only, so drawing evicts cells still on screen.
+    small.set_cell_cache_capacity(4096);
+    let mut small = draw(small, "abcdefghijklmnopqr");
+    small.backend_mut().redraw();
+
+    let large = draw(SoftBackend::new_with_font(20, 4, 16.0, FONT_DATA), "abcdefghijklmnopqr");
+    assert_eq!(small.backend().get_pixmap_data(), large.backend().get_pixmap_data());
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::{backend, cell_is, draw_cell};
+use ratatui::style::{Color, Style};
+use soft_ratatui::{CellText, GlyphContent, GlyphImage, GlyphRenderer, SoftBackend};
+
+/// Draws every cell as a 16x16 mask holding each coverage value from 0 to 255 once.
+struct Gradient;
+
+impl GlyphRenderer for Gradient {
+    fn set_size(&mut self, _font_size: f32, _scale_factor: f32) -> (usize, usize) {
+        (16, 16)
+    }
+
+    fn render(&mut self, _text: &CellText) -> Vec<GlyphImage> {
+        vec![GlyphImage {
+            left: 0,
+            top: 0,
+            width: 16,
+            height: 16,
+            content: GlyphContent::Mask,
+            data: (0..=255).collect(),
+        }]
+    }
+}
+
+/// Returns the pixels of the gradient drawn white on black with the given gamma and contrast.
+fn gradient(gamma: f32, contrast: f32) -> Vec<u8> {
+    let mut backend = SoftBackend::new_with_renderer(1, 1, 16.0, Gradient, 1.0);
+    backend.set_text_gamma(gamma, contrast);
+    draw_cell(&mut backend, 0, 0, "x", Style::new().fg(Color::White).bg(Color::Black));
+    backend.rgb_pixmap.data().to_vec()
+}
+
+/// The rgb values the backend draws for every named color.
+const NAMED_COLORS: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::Red, [139, 0, 0]),
+    (Color::Green, [0, 100, 0]),
+    (Color::Yellow, [255, 215, 0]),
+    (Color::Blue, [0, 0, 139]),
+    (Color::Magenta, [99, 9, 99]),
+    (Color::Cyan, [0, 0, 255]),
+    (Color::Gray, [128, 128, 128]),
+    (Color::DarkGray, [64, 64, 64]),
+    (Color::LightRed, [255, 0, 0]),
+    (Color::LightGreen, [0, 255, 0]),
+    (Color::LightBlue, [173, 216, 230]),
+    (Color::LightYellow, [255, 255, 224]),
+    (Color::LightMagenta, [139, 0, 139]),
+    (Color::LightCyan, [224, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+#[test]
+fn named_background_colors() {
+    let mut backend = backend(16, 1);
+    for (x, (color, rgb)) in NAMED_COLORS.iter().enumerate() {
+        draw_cell(&mut backend, x as u16, 0, " ", Style::new().bg(*color));
+        assert!(cell_is(&backend, x as u16, 0, *rgb), "{color:?}");
+    }
+}
+
+#[test]
+fn named_foreground_colors() {
+    let mut backend = backend(16, 1);
+    for (x, (color, rgb)) in NAMED_COLORS.iter().enumerate() {
+        draw_cell(&mut backend, x as u16, 0, "#", Style::new().fg(*color));
+        assert!(cell_is(&backend, x as u16, 0, *rgb), "{color:?}");
+    }
+}
+
+#[test]
+fn reset_colors() {
+    let mut backend = backend(2, 1);
+    assert!(cell_is(&backend, 0, 0, [24, 24, 24]));
+    draw_cell(&mut backend, 0, 0, "#", Style::new().fg(Color::Reset));
+    assert!(cell_is(&backend, 0, 0, [215, 215, 215]));
+}
+
+#[test]
+fn rgb_colors() {
+    let mut backend = backend(2, 1);
+    draw_cell(&mut backend, 0, 0, " ", Style::new().bg(Color::Rgb(1, 2, 3)));
+    assert!(cell_is(&backend, 0, 0, [1, 2, 3]));
+    draw_cell(&mut backend, 1, 0, "#", Style::new().fg(Color::Rgb(250, 128, 7)));
+    assert!(cell_is(&backend, 1, 0, [250, 128, 7]));
+}
+
+#[test]
+fn indexed_colors() {
+    let mut backend = backend(2, 1);
+    draw_cell(&mut backend, 0, 0, " ", Style::new().bg(Color::Indexed(10)));
+    assert!(cell_is(&backend, 0, 0, [100, 20, 10]));
+}
+
+#[test]
+fn monochrome_keeps_text_readable() {
+    let mut backend = backend(2, 1);
+    backend.set_monochrome(true);
+    // Both colors are dark, the text becomes white on black.
+    draw_cell(&mut backend, 0, 0, "#", Style::new().fg(Color::Red).bg(Color::Blue));
+    assert!(cell_is(&backend, 0, 0, [255, 255, 255]));
+    draw_cell(&mut backend, 1, 0, " ", Style::new().bg(Color::LightYellow));
+    assert!(cell_is(&backend, 1, 0, [255, 255, 255]));
+}
+
+#[test]
+fn text_gamma_and_contrast_are_clamped() {
+    let default = gradient(1.0, 1.0);
+    assert_eq!(default[..3], [0, 0, 0]);
+    assert_eq!(default[default.len() - 3..], [255, 255, 255]);
+
+    for (gamma, contrast, clamped_gamma, clamped_contrast) in [
+        (0.0, 1.0, 0.1, 1.0),
+        (-2.0, 1.0, 0.1, 1.0),
+        (f32::INFINITY, 1.0, 10.0, 1.0),
+        (1.0, -1.0, 1.0, 0.1),
+        (1.0, 0.0, 1.0, 0.1),
+        (1.0, 1000.0, 1.0, 10.0),
+        (f32::NAN, f32::NAN, 1.0, 1.0),
+    ] {
+        let pixels = gradient(gamma, contrast);
+        assert_eq!(pixels, gradient(clamped_gamma, clamped_contrast), "gamma {gamma}, contrast {contrast}");
+        // Full coverage stays visible and no coverage stays background.
+        assert_ne!(pixels[pixels.len() - 3..], [0, 0, 0], "gamma {gamma}, contrast {contrast}");
+        assert_eq!(pixels[..3], [0, 0, 0], "gamma {gamma}, contrast {contrast}");
+    }
+}
@@ -0,0 +1,85 @@
+//! Helpers shared by the integration tests.
+//!
+//! Most tests draw with a tiny 8x8 bitmap font, so every pixel is known in advance
+//! and the tests run without cosmic-text or installed fonts.
+
+#![allow(dead_code)]
+
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::style::Style;
+use soft_ratatui::{BitmapFont, SoftBackend};
+
+/// Width and height of a cell of the test font in pixels.
+pub const CELL: usize = 8;
+
+/// An 8x8 font with two glyphs: `I` is a vertical bar in column 3 from row 1 to row 6,
+/// `#` covers the whole cell.
+const TEST_BDF: &str = "\
+STARTFONT 2.1
+FONTBOUNDINGBOX 8 8 0 0
+STARTCHAR I
+ENCODING 73
+BBX 8 8 0 0
+BITMAP
+00
+10
+10
+10
+10
+10
+10
+00
+ENDCHAR
+STARTCHAR block
+ENCODING 35
+BBX 8 8 0 0
+BITMAP
+FF
+FF
+FF
+FF
+FF
+FF
+FF
+FF
+ENDCHAR
+ENDFONT
+";
+
+/// Creates a backend drawing with the test font.
+pub fn backend(width: u16, height: u16) -> SoftBackend {
+    let font = BitmapFont::from_bdf(TEST_BDF).unwrap();
+    SoftBackend::new_with_bitmap_font(width, height, font, 1.0)
+}
+
+/// Draws a single cell through [`Backend::draw`], like a terminal flushing a one cell diff.
+pub fn draw_cell(backend: &mut SoftBackend, x: u16, y: u16, symbol: &'static str, style: Style) {
+    let mut cell = Cell::new(symbol);
+    cell.set_style(style);
+    backend.draw([(x, y, &cell)].into_iter()).unwrap();
+}
+
+/// Returns the pixel at (px, py) inside the cell at column `x` and row `y`.
+pub fn pixel(backend: &SoftBackend, x: u16, y: u16, px: usize, py: usize) -> [u8; 3] {
+    let (cell_width, cell_height) = (backend.char_width, backend.char_height);
+    backend
+        .rgb_pixmap
+        .get_pixel(x as usize * cell_width + px, y as usize * cell_height + py)
+}
+
+/// Returns the pixels of the cell at column `x` and row `y`, row by row.
+pub fn cell_pixels(backend: &SoftBackend, x: u16, y: u16) -> Vec<[u8; 3]> {
+    let mut pixels = Vec::new();
+    for py in 0..backend.char_height {
+        for px in 0..backend.char_width {
+            pixels.push(pixel(backend, x, y, px, py));
+        }
+    }
+    pixels
+}
+
+/// Returns true when every pixel of the cell has the given color.
+pub fn cell_is(backend: &SoftBackend, x: u16, y: u16, color: [u8; 3]) -> bool {
+    cell_pixels(backend, x, y).iter().all(|&pixel| pixel == color)
+}
@@ -0,0 +1,49 @@
+//! The cosmic-text renderer draws single ASCII characters without shaping, these tests check it against shaping.
+#![cfg(feature = "cosmic")]
+
+use ratatui::style::Modifier;
+use soft_ratatui::{Antialiasing, CellText, CosmicRenderer, GlyphContent, GlyphImage, GlyphRenderer};
+
+static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+
+fn renderer(fast_path: bool, font_size: f32) -> CosmicRenderer {
+    let mut renderer = CosmicRenderer::from_font_data(FONT_DATA);
+    renderer.set_ascii_fast_path(fast_path);
+    renderer.set_size(font_size, 1.0);
+    renderer
+}
+
+/// Position, size, format and pixels of a glyph image, which has no `PartialEq`.
+type Glyph = (i32, i32, usize, usize, GlyphContent, Vec<u8>);
+
+fn render(renderer: &mut CosmicRenderer, symbol: &str, modifier: Modifier) -> Vec<Glyph> {
+    let text = CellText {
+        symbol,
+        modifier,
+        fg: [255, 255, 255],
+        bg: [0, 0, 0],
+        antialiasing: Antialiasing::Grayscale,
+    };
+    renderer
+        .render(&text)
+        .into_iter()
+        .map(|GlyphImage { left, top, width, height, content, data }| (left, top, width, height, content, data))
+        .collect()
+}
+
+#[test]
+fn ascii_fast_path_matches_shaping() {
+    for font_size in [16.0, 23.0] {
+        let mut fast = renderer(true, font_size);
+        let mut shaped = renderer(false, font_size);
+        for modifier in [Modifier::empty(), Modifier::BOLD, Modifier::ITALIC, Modifier::BOLD | Modifier::ITALIC] {
+            for c in ' '..='~' {
+                let symbol = c.to_string();
+                let images = render(&mut fast, &symbol, modifier);
+                assert_eq!(images, render(&mut shaped, &symbol, modifier), "{c:?} {modifier:?} at {font_size}");
+                let inked = images.iter().any(|image| image.5.iter().any(|&coverage| coverage > 0));
+                assert_eq!(inked, c != ' ', "{c:?} {modifier:?} at {font_size}");
+            }
+        }
+    }
+}
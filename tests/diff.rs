@@ -0,0 +1,62 @@
+//! Pixmap comparison, used by snapshot tests to describe how far a rendering strays from its golden image.
+
+use soft_ratatui::{PixelRect, RgbPixmap};
+
+fn gray(width: usize, height: usize, value: u8) -> RgbPixmap {
+    let mut pixmap = RgbPixmap::new(width, height);
+    pixmap.fill_rect(0, 0, width, height, [value; 3]);
+    pixmap
+}
+
+#[test]
+fn identical_pixmaps_do_not_differ() {
+    let pixmap = gray(16, 16, 90);
+    let diff = pixmap.diff(&pixmap);
+    assert!(diff.same_size());
+    assert_eq!(diff.max_delta(), 0);
+    assert_eq!(diff.count_over(0), 0);
+    assert_eq!(diff.bounds(0), None);
+    assert_eq!(diff.ssim(), 1.0);
+}
+
+#[test]
+fn changed_pixels_are_counted_and_bounded() {
+    let expected = gray(16, 16, 90);
+    let mut actual = expected.clone();
+    actual.put_pixel(3, 4, [90, 100, 90]);
+    actual.put_pixel(10, 12, [0, 90, 90]);
+    let diff = expected.diff(&actual);
+    assert_eq!(diff.max_delta(), 90);
+    assert_eq!(diff.count_over(0), 2);
+    assert_eq!(diff.count_over(10), 1);
+    assert_eq!(diff.bounds(0), Some(PixelRect { x: 3, y: 4, width: 8, height: 9 }));
+    assert_eq!(diff.bounds(10), Some(PixelRect { x: 10, y: 12, width: 1, height: 1 }));
+
+    let image = diff.to_image(10);
+    assert_eq!(image.get_pixel(10, 12), [255, 0, 0]);
+    let [r, g, b] = image.get_pixel(3, 4);
+    assert!(r == g && g == b, "pixels within the threshold are gray");
+}
+
+#[test]
+fn pixmaps_of_different_size_are_compared_over_the_larger_size() {
+    let expected = gray(8, 8, 0);
+    let actual = gray(10, 6, 0);
+    let diff = expected.diff(&actual);
+    assert!(!diff.same_size());
+    assert_eq!((diff.width(), diff.height()), (10, 8));
+    // Pixels present in only one of the pixmaps count as fully different.
+    assert_eq!(diff.max_delta(), 255);
+    assert_eq!(diff.count_over(254), 10 * 8 - 8 * 6);
+    assert_eq!(diff.ssim(), 0.0);
+}
+
+#[test]
+fn ssim_tolerates_faint_changes_but_not_new_shapes() {
+    let expected = gray(16, 16, 90);
+    let faint = gray(16, 16, 92);
+    let mut shape = expected.clone();
+    shape.fill_rect(4, 4, 4, 4, [255, 255, 255]);
+    assert!(expected.diff(&faint).ssim() > 0.99);
+    assert!(expected.diff(&shape).ssim() < 0.5);
+}
@@ -0,0 +1,156 @@
+//! Pixmaps are reduced to the gray levels of e-paper displays, which refresh only the areas that changed.
+
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::style::{Color, Style};
+use soft_ratatui::{Dithering, GrayDepth, PixelRect, RgbPixmap, SoftBackend, Tileset};
+
+/// A backend whose cells show only their background color.
+fn backend(width: u16, height: u16) -> SoftBackend {
+    let mut sheet = RgbPixmap::new(128, 128);
+    sheet.fill_rect(0, 0, 128, 128, [255, 0, 255]);
+    let tileset = Tileset::from_pixmap(&sheet, 8, 8, [255, 0, 255]).unwrap();
+    SoftBackend::new_with_tileset(width, height, tileset, 1.0)
+}
+
+/// Draws cells showing only a background color at the given cell positions.
+fn draw_backgrounds(backend: &mut SoftBackend, positions: &[(u16, u16)]) {
+    let mut cell = Cell::new(" ");
+    cell.set_style(Style::new().bg(Color::Rgb(90, 90, 90)));
+    backend.draw(positions.iter().map(|&(x, y)| (x, y, &cell))).unwrap();
+}
+
+fn rect(x: usize, y: usize, width: usize, height: usize) -> PixelRect {
+    PixelRect { x, y, width, height }
+}
+
+fn row(values: &[u8]) -> RgbPixmap {
+    let mut pixmap = RgbPixmap::new(values.len(), 1);
+    for (x, &value) in values.iter().enumerate() {
+        pixmap.put_pixel(x, 0, [value; 3]);
+    }
+    pixmap
+}
+
+#[test]
+fn threshold_rounds_to_the_nearest_level() {
+    let pixmap = row(&[0, 100, 200, 255]);
+    assert_eq!(pixmap.dither(GrayDepth::Mono, Dithering::Threshold).data(), &[0, 0, 1, 1]);
+    assert_eq!(pixmap.dither(GrayDepth::Gray4, Dithering::Threshold).data(), &[0, 1, 2, 3]);
+    assert_eq!(pixmap.dither(GrayDepth::Gray4, Dithering::Threshold).to_rgb().data(), row(&[0, 85, 170, 255]).data());
+}
+
+#[test]
+fn floyd_steinberg_keeps_the_brightness_of_a_gradient() {
+    let mut gradient = RgbPixmap::new(256, 8);
+    for y in 0..8 {
+        for x in 0..256 {
+            gradient.put_pixel(x, y, [x as u8; 3]);
+        }
+    }
+    let gray = gradient.dither(GrayDepth::Gray4, Dithering::FloydSteinberg);
+    let mut counts = [0usize; 4];
+    for &level in gray.data() {
+        assert!(level < 4, "level {level} out of range");
+        counts[level as usize] += 1;
+    }
+    // Black and white cover a sixth of the gradient each, the two grays a third each.
+    for (count, expected) in counts.into_iter().zip([341, 683, 683, 341]) {
+        assert!(count.abs_diff(expected) <= 16, "{counts:?}");
+    }
+    let brightness: usize = gray.to_rgb().data().iter().map(|&value| value as usize).sum();
+    assert_eq!(brightness / gray.to_rgb().data().len(), 127);
+}
+
+#[test]
+fn bayer_dithers_unchanged_areas_the_same_way() {
+    let mut frame = RgbPixmap::new(16, 8);
+    frame.fill([100, 100, 100]);
+    let before = frame.dither(GrayDepth::Mono, Dithering::Bayer);
+    frame.fill_rect(0, 0, 5, 8, [200, 200, 200]);
+    let after = frame.dither(GrayDepth::Mono, Dithering::Bayer);
+    for y in 0..8 {
+        for x in 5..16 {
+            assert_eq!(before.get_level(x, y), after.get_level(x, y));
+        }
+    }
+    assert!((0..8).any(|y| (0..5).any(|x| before.get_level(x, y) != after.get_level(x, y))));
+}
+
+#[test]
+fn gray4_packs_four_pixels_per_byte() {
+    let gray = row(&[255, 0, 85, 170, 255]).dither(GrayDepth::Gray4, Dithering::Threshold);
+    assert_eq!(gray.data(), &[3, 0, 1, 2, 3]);
+    // The fifth pixel starts a second byte, padded with zeros.
+    assert_eq!(gray.pack(), vec![0b1100_0110, 0b1100_0000]);
+    // Clipped to the last two pixels.
+    assert_eq!(gray.pack_rect(rect(3, 0, 10, 4)), vec![0b1011_0000]);
+    assert!(gray.pack_rect(rect(5, 0, 2, 1)).is_empty());
+
+    let mut pixmap = RgbPixmap::new(9, 2);
+    pixmap.fill([255, 255, 255]);
+    pixmap.put_pixel(8, 1, [0, 0, 0]);
+    let mono = pixmap.dither(GrayDepth::Mono, Dithering::Threshold);
+    assert_eq!(mono.pack(), vec![0xff, 0b1000_0000, 0xff, 0]);
+}
+
+#[test]
+fn monochrome_draws_only_black_and_white() {
+    let mut backend = backend(3, 1);
+    backend.set_monochrome(true);
+    let cells: Vec<(u16, u16, Cell)> = [Color::Rgb(200, 30, 30), Color::Rgb(40, 40, 90), Color::Yellow]
+        .into_iter()
+        .enumerate()
+        .map(|(x, color)| {
+            let mut cell = Cell::new(" ");
+            cell.set_style(Style::new().bg(color));
+            (x as u16, 0, cell)
+        })
+        .collect();
+    backend.draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell))).unwrap();
+    assert!(backend.get_pixmap_data().iter().all(|&value| value == 0 || value == 255));
+}
+
+#[test]
+fn dirty_rects_are_taken_once() {
+    let mut backend = backend(4, 2);
+    assert_eq!(backend.take_dirty_rects(8), vec![rect(0, 0, 32, 16)]);
+    assert!(backend.take_dirty_rects(8).is_empty());
+}
+
+#[test]
+fn dirty_rects_cover_only_the_changed_cells() {
+    let mut backend = backend(10, 4);
+    backend.take_dirty_rects(8);
+    draw_backgrounds(&mut backend, &[(1, 0), (6, 2), (7, 2)]);
+    let mut rects = backend.take_dirty_rects(8);
+    rects.sort_by_key(|rect| (rect.y, rect.x));
+    assert_eq!(rects, vec![rect(8, 0, 8, 8), rect(48, 16, 16, 8)]);
+}
+
+#[test]
+fn dirty_rects_are_aligned() {
+    let mut backend = backend(5, 1);
+    backend.take_dirty_rects(8);
+    draw_backgrounds(&mut backend, &[(1, 0)]);
+    assert_eq!(backend.take_dirty_rects(16), vec![rect(0, 0, 16, 8)]);
+    // Not widened past the right edge of the pixmap.
+    draw_backgrounds(&mut backend, &[(4, 0)]);
+    assert_eq!(backend.take_dirty_rects(16), vec![rect(32, 0, 8, 8)]);
+    // Rectangles touching once aligned are merged.
+    draw_backgrounds(&mut backend, &[(1, 0), (3, 0)]);
+    assert_eq!(backend.take_dirty_rects(16), vec![rect(0, 0, 32, 8)]);
+}
+
+#[test]
+fn dirty_rects_collapse_past_32_areas() {
+    let mut backend = backend(17, 8);
+    backend.take_dirty_rects(8);
+    // Every other cell of every other row, none of them touching.
+    let mut positions: Vec<(u16, u16)> = (0..4).flat_map(|y| (0..8).map(move |x| (x * 2, y * 2))).collect();
+    draw_backgrounds(&mut backend, &positions);
+    assert_eq!(backend.take_dirty_rects(8).len(), 32);
+    positions.push((16, 7));
+    draw_backgrounds(&mut backend, &positions);
+    assert_eq!(backend.take_dirty_rects(8), vec![rect(0, 0, 136, 64)]);
+}
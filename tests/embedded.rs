@@ -0,0 +1,131 @@
+//! Pixmaps are embedded-graphics draw targets and can be drawn onto embedded-graphics displays.
+#![cfg(feature = "embedded-graphics")]
+
+use embedded_graphics::mock_display::MockDisplay;
+use embedded_graphics::pixelcolor::{BinaryColor, Rgb565};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, Line, PrimitiveStyle};
+use embedded_graphics_core::Pixel;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{Point, Size};
+use embedded_graphics_core::pixelcolor::Rgb888;
+use embedded_graphics_core::primitives::Rectangle;
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::style::{Color, Style};
+use soft_ratatui::{RgbPixmap, SoftBackend, Tileset};
+
+const RED: Rgb888 = Rgb888::new(255, 0, 0);
+const BLUE: Rgb888 = Rgb888::new(0, 0, 255);
+
+/// A backend whose cells show only their background color.
+fn backend(width: u16, height: u16) -> SoftBackend {
+    let mut sheet = RgbPixmap::new(128, 128);
+    sheet.fill_rect(0, 0, 128, 128, [255, 0, 255]);
+    let tileset = Tileset::from_pixmap(&sheet, 8, 8, [255, 0, 255]).unwrap();
+    SoftBackend::new_with_tileset(width, height, tileset, 1.0)
+}
+
+fn fill_cell(backend: &mut SoftBackend, x: u16, y: u16, color: Color) {
+    let mut cell = Cell::new(" ");
+    cell.set_style(Style::new().bg(color));
+    backend.draw([(x, y, &cell)].into_iter()).unwrap();
+}
+
+#[test]
+fn pixels_outside_the_pixmap_are_dropped() {
+    let mut pixmap = RgbPixmap::new(4, 4);
+    let pixels = [(-1, 0), (0, -1), (4, 0), (0, 4), (2, 3)].map(|(x, y)| Pixel(Point::new(x, y), RED));
+    pixmap.draw_iter(pixels).unwrap();
+    let mut expected = RgbPixmap::new(4, 4);
+    expected.put_pixel(2, 3, [255, 0, 0]);
+    assert_eq!(pixmap.data(), expected.data());
+}
+
+#[test]
+fn primitives_are_drawn_and_clipped() {
+    let draw = |target: &mut RgbPixmap, offset: Point| {
+        Line::new(Point::new(-3, -1), Point::new(9, 5))
+            .translate(offset)
+            .into_styled(PrimitiveStyle::with_stroke(RED, 1))
+            .draw(target)
+            .unwrap();
+        Circle::new(Point::new(4, -2), 7)
+            .translate(offset)
+            .into_styled(PrimitiveStyle::with_fill(BLUE))
+            .draw(target)
+            .unwrap();
+    };
+    let mut pixmap = RgbPixmap::new(8, 4);
+    draw(&mut pixmap, Point::zero());
+    // The same primitives with room around them, not clipped at all.
+    let mut unclipped = RgbPixmap::new(20, 16);
+    draw(&mut unclipped, Point::new(6, 6));
+
+    for y in 0..4 {
+        for x in 0..8 {
+            assert_eq!(pixmap.get_pixel(x, y), unclipped.get_pixel(x + 6, y + 6), "({x}, {y})");
+        }
+    }
+    assert!(pixmap.data().chunks_exact(3).any(|pixel| pixel == [255, 0, 0]));
+    assert!(pixmap.data().chunks_exact(3).any(|pixel| pixel == [0, 0, 255]));
+    assert_eq!(unclipped.get_pixel(6 - 3, 6 - 1), [255, 0, 0]);
+}
+
+#[test]
+fn solid_fills_are_clipped() {
+    let mut pixmap = RgbPixmap::new(4, 4);
+    pixmap.fill_solid(&Rectangle::new(Point::new(-2, 2), Size::new(4, 10)), RED).unwrap();
+    for y in 0..4 {
+        for x in 0..4 {
+            let expected = if x < 2 && y >= 2 { [255, 0, 0] } else { [0, 0, 0] };
+            assert_eq!(pixmap.get_pixel(x, y), expected, "({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn backend_draws_onto_a_target_at_an_offset() {
+    let mut backend = backend(2, 1);
+    fill_cell(&mut backend, 1, 0, Color::Rgb(10, 20, 30));
+
+    let mut display = RgbPixmap::new(20, 10);
+    backend.draw_to_target(&mut display, Point::new(2, 1)).unwrap();
+    for y in 0..8 {
+        for x in 0..16 {
+            assert_eq!(display.get_pixel(x + 2, y + 1), backend.rgb_pixmap.get_pixel(x, y));
+        }
+    }
+    assert_eq!(display.get_pixel(10, 1), [10, 20, 30]);
+    assert_eq!(display.get_pixel(1, 1), [0, 0, 0]);
+    assert_eq!(display.get_pixel(18, 9), [0, 0, 0]);
+}
+
+#[test]
+fn colors_are_converted_for_the_display() {
+    let mut backend = backend(2, 1);
+    fill_cell(&mut backend, 0, 0, Color::Rgb(255, 128, 0));
+    fill_cell(&mut backend, 1, 0, Color::Rgb(40, 40, 40));
+
+    let origin = Point::new(3, 2);
+    let mut lcd = MockDisplay::<Rgb565>::new();
+    backend.draw_to_target(&mut lcd, origin).unwrap();
+    let mut epaper = MockDisplay::<BinaryColor>::new();
+    backend.draw_to_target(&mut epaper, origin).unwrap();
+
+    for y in 0..8 {
+        for x in 0..16 {
+            let point = origin + Point::new(x, y);
+            // Channels are rounded to 5 and 6 bits, luminance from half on is on.
+            let (rgb565, binary) = if x < 8 {
+                (Rgb565::new(31, 32, 0), BinaryColor::On)
+            } else {
+                (Rgb565::new(5, 10, 5), BinaryColor::Off)
+            };
+            assert_eq!(lcd.get_pixel(point), Some(rgb565), "{point:?}");
+            assert_eq!(epaper.get_pixel(point), Some(binary), "{point:?}");
+        }
+    }
+    let area = Rectangle::new(origin, Size::new(16, 8));
+    assert_eq!((lcd.affected_area(), epaper.affected_area()), (area, area));
+}
@@ -0,0 +1,85 @@
+//! Font size changes need a scalable font, these tests draw with cosmic-text and the font in `assets`.
+#![cfg(feature = "cosmic")]
+
+use ratatui::Terminal;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Paragraph};
+use soft_ratatui::{SoftBackend, ZoomPolicy};
+
+static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+
+fn backend(width: u16, height: u16, font_size: f32) -> SoftBackend {
+    SoftBackend::new_with_font(width, height, font_size, FONT_DATA)
+}
+
+#[test]
+fn cells_grow_with_the_font() {
+    let small = backend(10, 4, 16.0);
+    let large = backend(10, 4, 32.0);
+    assert!(large.char_width > small.char_width);
+    assert!(large.char_height > small.char_height);
+    assert_eq!(small.get_pixmap_width(), 10 * small.char_width);
+    assert_eq!(large.get_pixmap_height(), 4 * large.char_height);
+}
+
+#[test]
+fn set_font_size_matches_a_new_backend() {
+    let mut backend_16 = backend(10, 4, 16.0);
+    backend_16.set_font_size(24.0);
+    let backend_24 = backend(10, 4, 24.0);
+    assert_eq!(
+        (backend_16.char_width, backend_16.char_height),
+        (backend_24.char_width, backend_24.char_height)
+    );
+    assert_eq!(backend_16.get_pixmap_data(), backend_24.get_pixmap_data());
+}
+
+#[test]
+fn zoom_preserves_the_grid_or_the_pixels() {
+    let mut backend = backend(10, 4, 16.0);
+    backend.set_zoom_step(4.0);
+    backend.zoom_in();
+    assert_eq!(backend.font_size(), 20.0);
+    assert_eq!(backend.buffer().area.width, 10);
+    assert_eq!(backend.get_pixmap_width(), 10 * backend.char_width);
+
+    backend.reset_zoom();
+    let width = backend.get_pixmap_width();
+    backend.set_zoom_policy(ZoomPolicy::PreservePixels);
+    backend.zoom_in();
+    assert!(backend.buffer().area.width < 10);
+    assert!(backend.get_pixmap_width() <= width);
+}
+
+#[test]
+fn text_is_drawn() {
+    let mut terminal = Terminal::new(backend(10, 2, 16.0)).unwrap();
+    let blank = terminal.backend().rgb_pixmap.clone();
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new("Hello"), frame.area()))
+        .unwrap();
+    let diff = blank.diff(&terminal.backend().rgb_pixmap);
+    let bounds = diff.bounds(0).unwrap();
+    // Only the first row of cells, covering the five characters.
+    assert!(bounds.y + bounds.height <= terminal.backend().char_height);
+    assert!(bounds.x + bounds.width <= 5 * terminal.backend().char_width);
+}
+
+#[test]
+fn partial_draws_match_full_redraw() {
+    let mut terminal = Terminal::new(backend(24, 6, 16.0)).unwrap();
+    let style = Style::new()
+        .fg(Color::LightGreen)
+        .bg(Color::Indexed(17))
+        .add_modifier(Modifier::ITALIC | Modifier::CROSSED_OUT);
+    for text in ["Hello world", "Hallo Welt!", "gjpqy WMW"] {
+        terminal
+            .draw(|frame| {
+                frame.render_widget(Paragraph::new(text).style(style).block(Block::bordered()), frame.area())
+            })
+            .unwrap();
+    }
+    let partial = terminal.backend().rgb_pixmap.clone();
+    terminal.backend_mut().redraw();
+    assert_eq!(partial.data(), terminal.backend().rgb_pixmap.data());
+}
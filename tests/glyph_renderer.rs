@@ -0,0 +1,107 @@
+//! A custom glyph renderer gets the text of every cell and its images are blended by the backend.
+
+use std::sync::{Arc, Mutex};
+
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::style::{Color, Modifier, Style};
+use soft_ratatui::{CellText, GlyphContent, GlyphImage, GlyphRenderer, SoftBackend};
+
+const FG: [u8; 3] = [255, 255, 0];
+const BG: [u8; 3] = [0, 0, 128];
+
+/// Symbol, modifier, foreground and background of every rendered cell.
+type Seen = Arc<Mutex<Vec<(String, Modifier, [u8; 3], [u8; 3])>>>;
+
+/// Draws non-blank cells as a solid block overflowing the 4x8 cell by one pixel on every side,
+/// with too little data for question marks, and records the text it was asked to render.
+struct Blocks {
+    seen: Seen,
+}
+
+impl GlyphRenderer for Blocks {
+    fn set_size(&mut self, _font_size: f32, scale_factor: f32) -> (usize, usize) {
+        ((4.0 * scale_factor) as usize, (8.0 * scale_factor) as usize)
+    }
+
+    fn render(&mut self, text: &CellText) -> Vec<GlyphImage> {
+        self.seen.lock().unwrap().push((text.symbol.to_string(), text.modifier, text.fg, text.bg));
+        if text.symbol.trim().is_empty() {
+            return Vec::new();
+        }
+        // A row short for question marks.
+        let rows = if text.symbol == "?" { 9 } else { 10 };
+        vec![GlyphImage {
+            left: -1,
+            top: -1,
+            width: 6,
+            height: 10,
+            content: GlyphContent::Mask,
+            data: vec![255; 6 * rows],
+        }]
+    }
+}
+
+fn backend(scale_factor: f32) -> (SoftBackend, Seen) {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let backend = SoftBackend::new_with_renderer(3, 3, 16.0, Blocks { seen: seen.clone() }, scale_factor);
+    (backend, seen)
+}
+
+fn style() -> Style {
+    Style::new().fg(Color::Rgb(FG[0], FG[1], FG[2])).bg(Color::Rgb(BG[0], BG[1], BG[2]))
+}
+
+#[test]
+fn renderer_sets_the_cell_size() {
+    let (backend, _) = backend(2.0);
+    assert_eq!((backend.get_pixmap_width(), backend.get_pixmap_height()), (3 * 8, 3 * 16));
+}
+
+#[test]
+fn renderer_gets_the_text_and_colors_of_a_cell() {
+    let (mut backend, seen) = backend(1.0);
+    seen.lock().unwrap().clear();
+    let mut cell = Cell::new("A");
+    let modifier = Modifier::BOLD | Modifier::ITALIC | Modifier::UNDERLINED;
+    cell.set_style(style().add_modifier(modifier));
+    backend.draw([(1, 1, &cell)].into_iter()).unwrap();
+    // Underlines travel as combining marks, only bold and italic remain as modifiers.
+    let expected = ("A\u{332}".to_string(), Modifier::BOLD | Modifier::ITALIC, FG, BG);
+    assert!(seen.lock().unwrap().contains(&expected));
+}
+
+#[test]
+fn glyph_images_are_blended_and_clipped() {
+    let (mut backend, _) = backend(1.0);
+    let mut cell = Cell::new("A");
+    cell.set_style(style());
+    let mut blank = Cell::new(" ");
+    blank.set_style(Style::new().bg(Color::Rgb(BG[0], BG[1], BG[2])));
+    let cells = (0..3).flat_map(|y| (0..3).map(move |x| (x, y)));
+    let cells: Vec<(u16, u16, Cell)> =
+        cells.map(|(x, y)| (x, y, if (x, y) == (1, 1) { cell.clone() } else { blank.clone() })).collect();
+    backend.draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell))).unwrap();
+
+    for y in 8..16 {
+        for x in 4..8 {
+            assert_eq!(backend.rgb_pixmap.get_pixel(x, y), FG);
+        }
+    }
+    // The overflow to the right and below reaches the neighbours, above and left of the cell it is dropped.
+    assert_eq!(backend.rgb_pixmap.get_pixel(8, 8), FG);
+    assert_eq!(backend.rgb_pixmap.get_pixel(4, 16), FG);
+    assert_eq!(backend.rgb_pixmap.get_pixel(3, 8), BG);
+    assert_eq!(backend.rgb_pixmap.get_pixel(4, 7), BG);
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "glyph image data does not match its size"))]
+fn malformed_glyph_images_are_skipped() {
+    let (mut backend, _) = backend(1.0);
+    let mut cell = Cell::new("?");
+    cell.set_style(style());
+    backend.draw([(1, 1, &cell)].into_iter()).unwrap();
+    // Reached in release builds only, debug builds assert that renderers return well-formed images.
+    assert!((4..8).all(|x| (8..16).all(|y| backend.rgb_pixmap.get_pixel(x, y) == BG)));
+}
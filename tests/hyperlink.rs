@@ -0,0 +1,111 @@
+mod common;
+
+use common::{CELL, backend, pixel};
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::layout::Position;
+use ratatui::style::{Color, Style};
+use soft_ratatui::{Selection, SelectionMode, SoftBackend};
+
+const WHITE: [u8; 3] = [255, 255, 255];
+const BLACK: [u8; 3] = [0, 0, 0];
+const URL: &str = "https://ratatui.rs";
+
+fn cell(symbol: &str) -> Cell {
+    let mut cell = Cell::default();
+    cell.set_symbol(symbol).set_style(Style::new().fg(Color::White).bg(Color::Black));
+    cell
+}
+
+/// Draws a link like ratatui's hyperlink example: the plain text first,
+/// then every second cell is replaced by an OSC 8 sequence wrapping two characters.
+fn draw_link(backend: &mut SoftBackend, x: u16, y: u16, text: &str, terminator: &str) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut cells: Vec<(u16, u16, Cell)> = Vec::new();
+    for (i, c) in chars.iter().enumerate() {
+        cells.push((x + i as u16, y, cell(&c.to_string())));
+    }
+    for (i, pair) in chars.chunks(2).enumerate() {
+        let pair: String = pair.iter().collect();
+        let symbol = format!("\x1b]8;;{URL}{terminator}{pair}\x1b]8;;{terminator}");
+        cells.push((x + 2 * i as u16, y, cell(&symbol)));
+    }
+    backend.draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell))).unwrap();
+}
+
+fn draw_text(backend: &mut SoftBackend, x: u16, y: u16, text: &str) {
+    let cells: Vec<(u16, u16, Cell)> = text
+        .chars()
+        .enumerate()
+        .map(|(i, c)| (x + i as u16, y, cell(&c.to_string())))
+        .collect();
+    backend.draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell))).unwrap();
+}
+
+#[test]
+fn links_are_drawn_like_their_text() {
+    let mut linked = backend(8, 2);
+    draw_link(&mut linked, 1, 0, "I#I#I", "\x07");
+    let mut plain = backend(8, 2);
+    draw_text(&mut plain, 1, 0, "I#I#I");
+    assert_eq!(linked.rgb_pixmap.data(), plain.rgb_pixmap.data());
+}
+
+#[test]
+fn link_at_cell_covers_the_link_text() {
+    let mut backend = backend(12, 2);
+    draw_link(&mut backend, 2, 1, "hello", "\x07");
+    draw_link(&mut backend, 2, 0, "hi", "\x1b\\");
+
+    assert_eq!(backend.link_at_cell(Position::new(1, 1)), None);
+    for x in 2..7 {
+        assert_eq!(backend.link_at_cell(Position::new(x, 1)), Some(URL));
+    }
+    assert_eq!(backend.link_at_cell(Position::new(7, 1)), None);
+    // String terminator instead of BEL.
+    assert_eq!(backend.link_at_cell(Position::new(3, 0)), Some(URL));
+    assert_eq!(backend.link_at_cell(Position::new(4, 0)), None);
+    assert_eq!(backend.link_at_cell(Position::new(40, 0)), None);
+}
+
+#[test]
+fn hovered_links_are_underlined() {
+    let mut backend = backend(12, 2);
+    draw_text(&mut backend, 0, 0, "  ");
+    draw_link(&mut backend, 2, 0, "II", "\x07");
+    let underline = |backend: &SoftBackend, x: u16| pixel(backend, x, 0, 0, CELL - 1);
+
+    backend.set_hovered_cell(Some(Position::new(3, 0)));
+    assert_eq!((underline(&backend, 1), underline(&backend, 2), underline(&backend, 3)), (BLACK, WHITE, WHITE));
+    let partial = backend.rgb_pixmap.clone();
+    backend.redraw();
+    assert_eq!(partial.data(), backend.rgb_pixmap.data());
+
+    backend.set_hovered_cell(Some(Position::new(1, 0)));
+    assert_eq!((underline(&backend, 2), underline(&backend, 3)), (BLACK, BLACK));
+
+    // Links drawn under the mouse are underlined right away.
+    backend.set_hovered_cell(Some(Position::new(6, 1)));
+    draw_link(&mut backend, 5, 1, "IIII", "\x07");
+    assert_eq!(pixel(&backend, 8, 1, 0, CELL - 1), WHITE);
+    backend.set_hovered_cell(None);
+    assert_eq!(pixel(&backend, 8, 1, 0, CELL - 1), BLACK);
+}
+
+#[test]
+fn extracted_text_has_no_escape_sequences() {
+    let mut backend = backend(12, 1);
+    draw_link(&mut backend, 0, 0, "docs", "\x07");
+    draw_text(&mut backend, 4, 0, " ok");
+    backend.draw([(7, 0, &cell("\x1b[1m!\x1b[0m"))].into_iter()).unwrap();
+
+    assert_eq!(backend.screen_text().lines[0].text, "docs ok!");
+    backend.set_selection(Some(Selection::new(
+        Position::new(0, 0),
+        Position::new(11, 0),
+        SelectionMode::Linear,
+    )));
+    assert_eq!(backend.selected_text().unwrap(), "docs ok!");
+    backend.select_word(Position::new(1, 0));
+    assert_eq!(backend.selected_text().unwrap(), "docs");
+}
@@ -0,0 +1,317 @@
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell as StdCell;
+
+use common::{CELL, backend, cell_is, draw_cell, pixel};
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use soft_ratatui::{RgbPixmap, SoftBackend};
+
+const RED: [u8; 3] = [255, 0, 0];
+const BLUE: [u8; 3] = [0, 0, 255];
+const BACKGROUND: [u8; 3] = [24, 24, 24];
+
+/// Records the largest allocation of each thread, to check that hostile payloads stay small.
+struct TrackingAllocator;
+
+thread_local! {
+    static LARGEST_ALLOCATION: StdCell<usize> = const { StdCell::new(0) };
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = LARGEST_ALLOCATION.try_with(|largest| largest.set(largest.get().max(layout.size())));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// Returns the largest allocation made while running `f` on this thread.
+fn largest_allocation(f: impl FnOnce()) -> usize {
+    LARGEST_ALLOCATION.with(|largest| largest.set(0));
+    f();
+    LARGEST_ALLOCATION.with(StdCell::get)
+}
+
+const MIB: usize = 1 << 20;
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (i, &byte)| value | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(value >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn draw_symbol(backend: &mut SoftBackend, x: u16, y: u16, symbol: &str) {
+    let mut cell = Cell::default();
+    cell.set_symbol(symbol);
+    backend.draw([(x, y, &cell)].into_iter()).unwrap();
+}
+
+fn red_and_blue() -> RgbPixmap {
+    let mut image = RgbPixmap::new(2, 1);
+    image.put_pixel(0, 0, RED);
+    image.put_pixel(1, 0, BLUE);
+    image
+}
+
+fn assert_matches_redraw(backend: &mut SoftBackend) {
+    let partial = backend.rgb_pixmap.clone();
+    backend.redraw();
+    assert_eq!(partial.data(), backend.rgb_pixmap.data());
+}
+
+#[test]
+fn draw_image_stretches_over_the_cells() {
+    let mut backend = backend(8, 4);
+    backend.draw_image(Rect::new(1, 1, 4, 2), &red_and_blue());
+    for y in 1..3 {
+        assert!(cell_is(&backend, 1, y, RED));
+        assert!(cell_is(&backend, 2, y, RED));
+        assert!(cell_is(&backend, 3, y, BLUE));
+        assert!(cell_is(&backend, 4, y, BLUE));
+    }
+    assert!(cell_is(&backend, 0, 1, BACKGROUND));
+    assert!(cell_is(&backend, 5, 1, BACKGROUND));
+    assert_matches_redraw(&mut backend);
+}
+
+#[test]
+fn images_stay_until_a_covered_cell_changes() {
+    let mut backend = backend(8, 4);
+    backend.draw_image(Rect::new(1, 1, 4, 2), &red_and_blue());
+
+    // Neighbouring cells are repainted without erasing the image.
+    draw_cell(&mut backend, 0, 1, "#", Style::new().fg(Color::White));
+    assert!(cell_is(&backend, 1, 1, RED));
+    assert!(cell_is(&backend, 1, 2, RED));
+
+    draw_cell(&mut backend, 4, 2, "#", Style::new().fg(Color::White));
+    assert!(cell_is(&backend, 1, 1, BACKGROUND));
+    assert!(cell_is(&backend, 4, 2, [255, 255, 255]));
+    assert_matches_redraw(&mut backend);
+}
+
+#[test]
+fn drawing_the_same_image_again_is_free() {
+    let mut backend = backend(8, 4);
+    backend.draw_image(Rect::new(1, 1, 4, 2), &red_and_blue());
+    backend.take_dirty_rects(1);
+    backend.draw_image(Rect::new(1, 1, 4, 2), &red_and_blue());
+    assert!(backend.take_dirty_rects(1).is_empty());
+
+    let mut other = red_and_blue();
+    other.put_pixel(0, 0, BLUE);
+    backend.draw_image(Rect::new(1, 1, 4, 2), &other);
+    assert!(cell_is(&backend, 1, 1, BLUE));
+
+    backend.clear_images();
+    assert!(cell_is(&backend, 1, 1, BACKGROUND));
+}
+
+#[test]
+fn sixel_images_keep_their_pixel_size() {
+    let mut backend = backend(8, 4);
+    // A 16x6 image: the top row red, the rest transparent, then a blue band of 16x6.
+    let sixel = "\x1bP0;1;0q\"1;1;16;12#1;2;100;0;0#1!16@-#2;2;0;0;100#2!16~\x1b\\";
+    draw_symbol(&mut backend, 1, 0, sixel);
+
+    assert_eq!(pixel(&backend, 1, 0, 0, 0), RED);
+    assert_eq!(pixel(&backend, 2, 0, 7, 0), RED);
+    assert_eq!(pixel(&backend, 1, 0, 0, 1), BACKGROUND);
+    assert_eq!(pixel(&backend, 1, 0, 0, 6), BLUE);
+    assert_eq!(pixel(&backend, 2, 1, 7, 3), BLUE);
+    assert_eq!(pixel(&backend, 1, 1, 0, 4), BACKGROUND);
+    assert!(cell_is(&backend, 3, 0, BACKGROUND));
+    assert_eq!(backend.screen_text().lines[0].text, "");
+    assert_matches_redraw(&mut backend);
+
+    // Replacing the symbol removes the image.
+    draw_symbol(&mut backend, 1, 0, " ");
+    assert!(cell_is(&backend, 1, 0, BACKGROUND));
+    assert!(cell_is(&backend, 2, 1, BACKGROUND));
+}
+
+#[test]
+fn kitty_images_fill_their_columns_and_rows() {
+    let mut backend = backend(8, 4);
+    let pixels = [RED, BLUE].concat();
+    let kitty = format!("\x1b_Ga=T,f=24,s=2,v=1,c=2,r=1;{}\x1b\\", base64(&pixels));
+    draw_symbol(&mut backend, 2, 3, &kitty);
+    assert!(cell_is(&backend, 2, 3, RED));
+    assert!(cell_is(&backend, 3, 3, BLUE));
+    assert!(cell_is(&backend, 4, 3, BACKGROUND));
+}
+
+#[test]
+fn kitty_chunks_and_placements() {
+    let mut backend = backend(8, 4);
+    // RGBA with a transparent second pixel, sent in two chunks and placed later by id.
+    let data = base64(&[255, 0, 0, 255, 0, 0, 255, 0]);
+    let (first, second) = data.split_at(4);
+    let transmit = format!("\x1b_Ga=t,f=32,s=2,v=1,i=7,m=1;{first}\x1b\\\x1b_Gm=0;{second}\x1b\\");
+    draw_symbol(&mut backend, 0, 0, &transmit);
+    assert!(cell_is(&backend, 0, 0, BACKGROUND));
+
+    draw_symbol(&mut backend, 4, 1, "\x1b_Ga=p,i=7,c=4,r=1\x1b\\");
+    assert!(cell_is(&backend, 4, 1, RED));
+    assert!(cell_is(&backend, 5, 1, RED));
+    assert!(cell_is(&backend, 6, 1, BACKGROUND));
+
+    // Deleting an unknown id keeps the image, deleting its id removes it.
+    draw_symbol(&mut backend, 0, 3, "\x1b_Ga=d,d=i,i=8\x1b\\");
+    assert!(cell_is(&backend, 4, 1, RED));
+    draw_symbol(&mut backend, 0, 2, "\x1b_Ga=d,d=i,i=7\x1b\\");
+    assert!(cell_is(&backend, 4, 1, BACKGROUND));
+}
+
+#[test]
+fn kitty_unicode_placeholders_give_the_size() {
+    let mut backend = backend(8, 4);
+    let pixels = [RED, BLUE, BLUE, RED].concat();
+    let row = "\x1b[38;5;1m\u{10EEEE}\u{305}\u{305}\u{10EEEE}\u{305}\u{30D}\u{10EEEE}\u{305}\u{30E}\x1b[39m";
+    let first = format!("\x1b_Ga=T,U=1,f=24,s=2,v=2,i=1,q=2;{}\x1b\\{row}", base64(&pixels));
+    let mut first_cell = Cell::default();
+    first_cell.set_symbol(&first);
+    let mut second_cell = Cell::default();
+    second_cell.set_symbol(row);
+    backend
+        .draw([(1, 1, &first_cell), (1, 2, &second_cell)].into_iter())
+        .unwrap();
+
+    // Three columns and two rows of placeholders.
+    assert_eq!(pixel(&backend, 1, 1, 0, 0), RED);
+    assert_eq!(pixel(&backend, 3, 1, 7, 7), BLUE);
+    assert_eq!(pixel(&backend, 1, 2, 0, 0), BLUE);
+    assert_eq!(pixel(&backend, 3, 2, 7, 7), RED);
+    assert!(cell_is(&backend, 4, 1, BACKGROUND));
+    assert!(cell_is(&backend, 1, 3, BACKGROUND));
+    assert_eq!(backend.screen_text().lines[1].text, "");
+}
+
+#[test]
+fn scale_factor_scales_protocol_images() {
+    let mut backend = backend(8, 4);
+    backend.set_scale_factor(2.0);
+    let sixel = "\x1bPq#1;2;100;0;0#1!8~\x1b\\";
+    draw_symbol(&mut backend, 0, 0, sixel);
+    // 8x6 logical pixels, 16x12 physical.
+    assert_eq!(backend.rgb_pixmap.get_pixel(15, 11), RED);
+    assert_eq!(backend.rgb_pixmap.get_pixel(16, 0), BACKGROUND);
+    assert_eq!(backend.rgb_pixmap.get_pixel(0, 12), BACKGROUND);
+}
+
+#[test]
+fn overlapping_images_replace_each_other() {
+    let mut backend = backend(8, 4);
+    let mut red = RgbPixmap::new(1, 1);
+    red.put_pixel(0, 0, RED);
+    let mut blue = RgbPixmap::new(1, 1);
+    blue.put_pixel(0, 0, BLUE);
+
+    backend.draw_image(Rect::new(0, 0, 4, 2), &red);
+    backend.draw_image(Rect::new(2, 1, 4, 2), &blue);
+    // The partly covered red image is gone, also where the blue one does not reach.
+    assert!(cell_is(&backend, 0, 0, BACKGROUND));
+    assert!(cell_is(&backend, 3, 0, BACKGROUND));
+    assert!(cell_is(&backend, 2, 1, BLUE));
+    assert!(cell_is(&backend, 5, 2, BLUE));
+    assert_matches_redraw(&mut backend);
+
+    // Images from protocols replace overlapping images the same way.
+    draw_symbol(&mut backend, 1, 2, "\x1bPq#1;2;100;0;0#1!16~\x1b\\");
+    assert!(cell_is(&backend, 4, 1, BACKGROUND));
+    assert_eq!(pixel(&backend, 2, 2, 0, 0), RED);
+    assert_matches_redraw(&mut backend);
+
+    // Images next to each other stay.
+    backend.draw_image(Rect::new(6, 0, 2, 2), &blue);
+    assert_eq!(pixel(&backend, 2, 2, 0, 0), RED);
+    assert!(cell_is(&backend, 6, 0, BLUE));
+}
+
+#[test]
+fn oversized_sixel_images_are_cropped() {
+    let mut backend = backend(8, 4);
+    // Raster attributes declaring the largest image, with one red sixel in it.
+    let declared = "\x1bPq\"1;1;8192;8192#1;2;100;0;0#1~\x1b\\";
+    assert!(largest_allocation(|| draw_symbol(&mut backend, 0, 0, declared)) < 32 * MIB);
+    assert_eq!(pixel(&backend, 0, 0, 0, 0), RED);
+
+    // Sixels far down and a huge repeat count grow the canvas only up to the pixel limit.
+    let far = format!("\x1bPq#1;2;100;0;0#1~{}!100000~\x1b\\", "-".repeat(10000));
+    assert!(largest_allocation(|| draw_symbol(&mut backend, 0, 1, &far)) < 32 * MIB);
+    assert_eq!(pixel(&backend, 0, 1, 0, 0), RED);
+
+    // A scaled up image only scales the part inside its cells.
+    backend.set_scale_factor(4.0);
+    let large = "\x1bPq\"1;1;2048;2048#1;2;100;0;0#1~\x1b\\";
+    assert!(largest_allocation(|| draw_symbol(&mut backend, 0, 2, large)) < 32 * MIB);
+    assert_eq!(backend.rgb_pixmap.get_pixel(0, 2 * CELL * 4), RED);
+    assert_matches_redraw(&mut backend);
+
+    // Malformed data draws what it can without panicking.
+    for sixel in ["\x1bPq\"\x1b\\", "\x1bPq!~#999;9;9\x1b\\", "\x1bPq!99999999999999999999999~\x1b\\"] {
+        draw_symbol(&mut backend, 1, 0, sixel);
+    }
+}
+
+#[test]
+fn malformed_kitty_images_are_skipped() {
+    let mut backend = backend(8, 4);
+    let pixels = base64(&[RED, BLUE].concat());
+    let payloads = [
+        // Larger than the largest image, in one dimension or in pixels.
+        format!("\x1b_Ga=T,f=24,s=100000,v=100000;{pixels}\x1b\\"),
+        format!("\x1b_Ga=T,f=24,s=4096,v=4096;{pixels}\x1b\\"),
+        // Less data than the size needs, invalid base64 and an unsupported format.
+        format!("\x1b_Ga=T,f=24,s=2,v=2;{pixels}\x1b\\"),
+        "\x1b_Ga=T,f=24,s=2,v=1;!!!!!!!!\x1b\\".to_string(),
+        format!("\x1b_Ga=T,f=100,s=2,v=1;{pixels}\x1b\\"),
+    ];
+    for payload in &payloads {
+        assert!(largest_allocation(|| draw_symbol(&mut backend, 1, 1, payload)) < MIB);
+        assert!(cell_is(&backend, 1, 1, BACKGROUND));
+    }
+}
+
+#[test]
+fn stored_kitty_images_are_limited() {
+    let mut backend = backend(8, 4);
+    // 12 MiB of pixels each, the stored images are limited to 32 MiB.
+    let pixels = base64(&RED.repeat(2048 * 2048));
+    let store = |backend: &mut SoftBackend, id: u32| {
+        draw_symbol(backend, 7, 3, &format!("\x1b_Ga=t,f=24,s=2048,v=2048,i={id};{pixels}\x1b\\"));
+    };
+    let place = |backend: &mut SoftBackend, id: u32, x: u16, y: u16| {
+        draw_symbol(backend, x, y, &format!("\x1b_Ga=p,i={id},c=1,r=1\x1b\\"));
+        cell_is(backend, x, y, RED)
+    };
+    store(&mut backend, 1);
+    assert!(place(&mut backend, 1, 0, 0));
+    for id in 2..=4 {
+        store(&mut backend, id);
+    }
+    // The oldest images without a placement made room, the placed one stays.
+    let resolved: Vec<u32> = (1..=4).filter(|&id| place(&mut backend, id, id as u16, 1)).collect();
+    assert_eq!(resolved, [1, 4]);
+    assert!(cell_is(&backend, 0, 0, RED));
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::{CELL, backend, cell_is, draw_cell, pixel};
+use ratatui::style::{Color, Modifier, Style};
+
+const WHITE: [u8; 3] = [255, 255, 255];
+const BLACK: [u8; 3] = [0, 0, 0];
+
+fn style(modifier: Modifier) -> Style {
+    Style::new()
+        .fg(Color::White)
+        .bg(Color::Black)
+        .add_modifier(modifier)
+}
+
+/// Returns which columns of a pixel row of the first cell are drawn in the foreground color.
+fn lit_columns(backend: &soft_ratatui::SoftBackend, row: usize) -> Vec<usize> {
+    (0..CELL)
+        .filter(|&column| pixel(backend, 0, 0, column, row) == WHITE)
+        .collect()
+}
+
+#[test]
+fn plain_text_uses_foreground_on_background() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, "I", style(Modifier::empty()));
+    assert_eq!(lit_columns(&backend, 0), Vec::<usize>::new());
+    for row in 1..7 {
+        assert_eq!(lit_columns(&backend, row), vec![3]);
+    }
+    assert_eq!(pixel(&backend, 0, 0, 0, 0), BLACK);
+}
+
+#[test]
+fn bold_thickens_strokes() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, "I", style(Modifier::BOLD));
+    for row in 1..7 {
+        assert_eq!(lit_columns(&backend, row), vec![3, 4]);
+    }
+}
+
+#[test]
+fn dim_darkens_foreground_and_background() {
+    let mut backend = backend(4, 2);
+    let dim = Style::new()
+        .fg(Color::White)
+        .bg(Color::Rgb(100, 200, 255))
+        .add_modifier(Modifier::DIM);
+    draw_cell(&mut backend, 0, 0, "I", dim);
+    assert_eq!(pixel(&backend, 0, 0, 3, 3), [77, 77, 77]);
+    assert_eq!(pixel(&backend, 0, 0, 0, 0), [30, 60, 77]);
+}
+
+#[test]
+fn italic_leans_to_the_right() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, "I", style(Modifier::ITALIC));
+    // One pixel of shift for every four rows above the bottom row.
+    assert_eq!(lit_columns(&backend, 1), vec![4]);
+    assert_eq!(lit_columns(&backend, 3), vec![4]);
+    assert_eq!(lit_columns(&backend, 4), vec![3]);
+    assert_eq!(lit_columns(&backend, 6), vec![3]);
+}
+
+#[test]
+fn underlined_fills_the_bottom_row() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, " ", style(Modifier::UNDERLINED));
+    assert_eq!(lit_columns(&backend, CELL - 1), (0..CELL).collect::<Vec<_>>());
+    assert_eq!(lit_columns(&backend, CELL - 2), Vec::<usize>::new());
+}
+
+#[test]
+fn crossed_out_fills_the_middle_row() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, "I", style(Modifier::CROSSED_OUT));
+    assert_eq!(lit_columns(&backend, CELL / 2), (0..CELL).collect::<Vec<_>>());
+    assert_eq!(lit_columns(&backend, CELL / 2 - 1), vec![3]);
+}
+
+#[test]
+fn reversed_swaps_foreground_and_background() {
+    let mut backend = backend(4, 2);
+    let reversed = Style::new()
+        .fg(Color::Red)
+        .bg(Color::Blue)
+        .add_modifier(Modifier::REVERSED);
+    draw_cell(&mut backend, 0, 0, "I", reversed);
+    assert_eq!(pixel(&backend, 0, 0, 0, 0), [139, 0, 0]);
+    assert_eq!(pixel(&backend, 0, 0, 3, 3), [0, 0, 139]);
+}
+
+#[test]
+fn hidden_draws_only_the_background() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 0, 0, "#", style(Modifier::HIDDEN));
+    assert!(cell_is(&backend, 0, 0, BLACK));
+}
+
+#[test]
+fn modifiers_do_not_leak_into_neighbours() {
+    let mut backend = backend(4, 2);
+    let all = Modifier::BOLD
+        | Modifier::ITALIC
+        | Modifier::UNDERLINED
+        | Modifier::CROSSED_OUT
+        | Modifier::REVERSED;
+    draw_cell(&mut backend, 1, 0, "I", style(all));
+    assert!(cell_is(&backend, 0, 0, [24, 24, 24]));
+    assert!(cell_is(&backend, 2, 0, [24, 24, 24]));
+    assert!(cell_is(&backend, 1, 1, [24, 24, 24]));
+}
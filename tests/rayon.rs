@@ -0,0 +1,69 @@
+//! Large updates are rasterized and painted in parallel bands of rows,
+//! they have to match updates small enough to be painted on one thread.
+#![cfg(all(feature = "rayon", feature = "cosmic"))]
+
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::style::{Color, Modifier, Style};
+use soft_ratatui::SoftBackend;
+
+static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+
+const WIDTH: u16 = 40;
+const HEIGHT: u16 = 20;
+
+fn cells() -> Vec<(u16, u16, Cell)> {
+    let mut cells = Vec::new();
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let index = (y * WIDTH + x) as usize;
+            let mut cell = Cell::default();
+            // Descenders and italics overflow into the neighbouring cells.
+            cell.set_symbol(&char::from(b'!' + (index % 94) as u8).to_string());
+            let mut style = Style::new().fg(Color::Indexed(index as u8)).bg(Color::Rgb(0, 0, (y * 8) as u8));
+            if index.is_multiple_of(3) {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            if index.is_multiple_of(5) {
+                style = style.add_modifier(Modifier::ITALIC);
+            }
+            cell.set_style(style);
+            cells.push((x, y, cell));
+        }
+    }
+    cells
+}
+
+fn draw(backend: &mut SoftBackend, cells: &[(u16, u16, Cell)]) {
+    backend.draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell))).unwrap();
+}
+
+/// Paints the cells one row at a time, which stays below the parallel threshold.
+fn serial() -> SoftBackend {
+    let mut serial = SoftBackend::new_with_font(WIDTH, HEIGHT, 16.0, FONT_DATA);
+    for row in cells().chunks(WIDTH as usize) {
+        draw(&mut serial, row);
+    }
+    serial
+}
+
+#[test]
+fn parallel_bands_match_serial_painting() {
+    let mut parallel = SoftBackend::new_with_font(WIDTH, HEIGHT, 16.0, FONT_DATA);
+    // 800 cells missing from the cache in one update, rasterized and painted in parallel.
+    draw(&mut parallel, &cells());
+    let serial = serial();
+    assert_eq!(parallel.get_pixmap_data(), serial.get_pixmap_data());
+
+    // Painted again from the cache.
+    parallel.redraw();
+    assert_eq!(parallel.get_pixmap_data(), serial.get_pixmap_data());
+}
+
+#[test]
+fn parallel_rasterization_without_a_cell_cache() {
+    let mut parallel = SoftBackend::new_with_font(WIDTH, HEIGHT, 16.0, FONT_DATA);
+    parallel.set_cell_cache_capacity(0);
+    draw(&mut parallel, &cells());
+    assert_eq!(parallel.get_pixmap_data(), serial().get_pixmap_data());
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::{CELL, backend, draw_cell};
+use ratatui::Terminal;
+use ratatui::backend::Backend;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Paragraph};
+use soft_ratatui::{CellText, GlyphContent, GlyphImage, GlyphRenderer, SoftBackend};
+
+/// Draws `#` as a block reaching half a cell into the neighbours right of and below it, other symbols stay inside the cell.
+struct Overflowing;
+
+impl GlyphRenderer for Overflowing {
+    fn set_size(&mut self, _font_size: f32, _scale_factor: f32) -> (usize, usize) {
+        (CELL, CELL)
+    }
+
+    fn render(&mut self, text: &CellText) -> Vec<GlyphImage> {
+        let size = match text.symbol {
+            "#" => CELL + CELL / 2,
+            "I" => CELL / 2,
+            _ => return Vec::new(),
+        };
+        vec![GlyphImage {
+            left: 0,
+            top: 0,
+            width: size,
+            height: size,
+            content: GlyphContent::Mask,
+            data: vec![255; size * size],
+        }]
+    }
+}
+
+fn frame(terminal: &mut Terminal<SoftBackend>, text: &str, area: Rect) {
+    terminal
+        .draw(|frame| {
+            let style = Style::new()
+                .fg(Color::Yellow)
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+            frame.render_widget(Paragraph::new(text).style(style).block(Block::bordered()), area);
+        })
+        .unwrap();
+}
+
+#[test]
+fn partial_draws_match_full_redraw() {
+    let mut terminal = Terminal::new(backend(20, 6)).unwrap();
+    frame(&mut terminal, "I#I#", Rect::new(0, 0, 10, 4));
+    frame(&mut terminal, "##II", Rect::new(4, 1, 12, 5));
+    frame(&mut terminal, "I", Rect::new(2, 2, 6, 3));
+
+    let partial = terminal.backend().rgb_pixmap.clone();
+    terminal.backend_mut().redraw();
+    assert_eq!(partial.data(), terminal.backend().rgb_pixmap.data());
+}
+
+#[test]
+fn partial_draws_match_a_single_draw() {
+    let mut incremental = Terminal::new(backend(20, 6)).unwrap();
+    frame(&mut incremental, "II", Rect::new(0, 0, 20, 6));
+    frame(&mut incremental, "I#I#", Rect::new(0, 0, 20, 6));
+
+    let mut direct = Terminal::new(backend(20, 6)).unwrap();
+    frame(&mut direct, "I#I#", Rect::new(0, 0, 20, 6));
+
+    assert_eq!(
+        incremental.backend().rgb_pixmap.data(),
+        direct.backend().rgb_pixmap.data()
+    );
+}
+
+#[test]
+fn clear_resets_the_grid() {
+    let mut terminal = Terminal::new(backend(8, 3)).unwrap();
+    let blank = terminal.backend().rgb_pixmap.clone();
+    frame(&mut terminal, "##", Rect::new(0, 0, 8, 3));
+    assert_ne!(blank.data(), terminal.backend().rgb_pixmap.data());
+
+    terminal.backend_mut().clear().unwrap();
+    assert_eq!(blank.data(), terminal.backend().rgb_pixmap.data());
+    terminal.backend_mut().redraw();
+    assert_eq!(blank.data(), terminal.backend().rgb_pixmap.data());
+}
+
+#[test]
+fn dirty_rects_cover_changed_cells() {
+    let mut terminal = Terminal::new(backend(20, 6)).unwrap();
+    terminal.backend_mut().take_dirty_rects(1);
+
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new("#"), Rect::new(3, 2, 1, 1)))
+        .unwrap();
+    let rects = terminal.backend_mut().take_dirty_rects(1);
+    assert_eq!(rects.len(), 1);
+    // Glyphs that stay inside their cell leave the neighbours alone.
+    assert_eq!((rects[0].x, rects[0].y, rects[0].width, rects[0].height), (24, 16, 8, 8));
+    assert!(terminal.backend_mut().take_dirty_rects(1).is_empty());
+}
+
+#[test]
+fn overflowing_glyphs_match_full_redraw() {
+    let mut backend = SoftBackend::new_with_renderer(6, 4, 16.0, Overflowing, 1.0);
+    let red = Style::new().fg(Color::Red).bg(Color::Blue);
+    let steps = [(1, 1, "#"), (2, 1, "I"), (2, 2, "#"), (1, 1, " "), (1, 2, "#"), (2, 2, "I"), (1, 2, " ")];
+    for (x, y, symbol) in steps {
+        draw_cell(&mut backend, x, y, symbol, red);
+        let partial = backend.rgb_pixmap.clone();
+        backend.redraw();
+        assert_eq!(partial.data(), backend.rgb_pixmap.data(), "after drawing {symbol:?} at ({x}, {y})");
+    }
+}
+
+#[test]
+fn overflowing_glyphs_repaint_their_neighbours() {
+    let mut backend = SoftBackend::new_with_renderer(6, 4, 16.0, Overflowing, 1.0);
+    backend.take_dirty_rects(1);
+
+    draw_cell(&mut backend, 3, 2, "#", Style::new());
+    let rects = backend.take_dirty_rects(1);
+    assert_eq!((rects[0].x, rects[0].y, rects[0].width, rects[0].height), (24, 16, 16, 16));
+
+    // Erasing it has to clear the overflow as well.
+    draw_cell(&mut backend, 3, 2, " ", Style::new());
+    let rects = backend.take_dirty_rects(1);
+    assert_eq!((rects[0].x, rects[0].y, rects[0].width, rects[0].height), (24, 16, 16, 16));
+    assert!(common::cell_is(&backend, 4, 3, [24, 24, 24]));
+}
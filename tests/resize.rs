@@ -0,0 +1,85 @@
+mod common;
+
+use common::{CELL, backend, cell_is, draw_cell};
+use ratatui::backend::Backend;
+use ratatui::layout::Size;
+use ratatui::style::{Color, Style};
+use soft_ratatui::Letterbox;
+
+const RED: [u8; 3] = [255, 0, 0];
+
+#[test]
+fn pixmap_matches_the_grid() {
+    let backend = backend(10, 4);
+    assert_eq!(backend.get_pixmap_width(), 10 * CELL);
+    assert_eq!(backend.get_pixmap_height(), 4 * CELL);
+    assert_eq!(backend.get_pixmap_data().len(), 10 * 4 * CELL * CELL * 3);
+}
+
+#[test]
+fn resize_grows_and_shrinks_the_pixmap() {
+    let mut backend = backend(4, 2);
+
+    backend.resize(6, 3);
+    assert_eq!(backend.size().unwrap(), Size::new(6, 3));
+    assert_eq!(backend.get_pixmap_width(), 6 * CELL);
+    assert_eq!(backend.get_pixmap_height(), 3 * CELL);
+    // Cells added by the resize can be drawn right away.
+    draw_cell(&mut backend, 5, 2, "#", Style::new().fg(Color::LightRed));
+    assert!(cell_is(&backend, 5, 2, RED));
+
+    backend.resize(2, 2);
+    assert_eq!(backend.size().unwrap(), Size::new(2, 2));
+    assert_eq!(backend.get_pixmap_width(), 2 * CELL);
+    draw_cell(&mut backend, 1, 1, "#", Style::new().fg(Color::LightRed));
+    assert!(cell_is(&backend, 1, 1, RED));
+}
+
+#[test]
+fn resize_to_pixels_fits_whole_cells() {
+    let mut backend = backend(4, 2);
+    let fit = backend.resize_to_pixels(100, 45);
+    assert_eq!((fit.columns, fit.rows), (12, 5));
+    assert_eq!((fit.margin_x, fit.margin_y), (4, 5));
+    assert_eq!(backend.get_pixmap_width(), 12 * CELL);
+    assert_eq!(backend.get_pixmap_height(), 5 * CELL);
+}
+
+#[test]
+fn letterbox_pads_around_the_grid() {
+    let mut backend = backend(4, 2);
+    backend.set_letterbox(Some(Letterbox {
+        centered: true,
+        padding_color: [1, 2, 3],
+    }));
+    backend.resize_to_pixels(100, 45);
+    assert_eq!(backend.get_pixmap_width(), 100);
+    assert_eq!(backend.get_pixmap_height(), 45);
+    assert_eq!(backend.grid_offset(), (2, 2));
+    assert_eq!(backend.rgb_pixmap.get_pixel(0, 0), [1, 2, 3]);
+    assert_eq!(backend.rgb_pixmap.get_pixel(99, 44), [1, 2, 3]);
+    assert_eq!(backend.rgb_pixmap.get_pixel(2, 2), [24, 24, 24]);
+}
+
+#[test]
+fn scale_factor_scales_cells() {
+    let mut backend = backend(4, 2);
+    draw_cell(&mut backend, 1, 0, "#", Style::new().fg(Color::LightRed));
+
+    backend.set_scale_factor(2.0);
+    assert_eq!(backend.get_pixmap_width(), 4 * CELL * 2);
+    assert_eq!(backend.get_pixmap_height(), 2 * CELL * 2);
+    // Logical cell size stays the same, the physical pixels double.
+    assert_eq!((backend.char_width, backend.char_height), (CELL, CELL));
+    assert_eq!(backend.rgb_pixmap.get_pixel(2 * CELL, 0), RED);
+    assert_eq!(backend.rgb_pixmap.get_pixel(4 * CELL - 1, 2 * CELL - 1), RED);
+    assert_eq!(backend.rgb_pixmap.get_pixel(4 * CELL, 0), [24, 24, 24]);
+}
+
+#[test]
+fn bitmap_fonts_ignore_font_size() {
+    let mut backend = backend(4, 2);
+    backend.set_font_size(32.0);
+    assert_eq!(backend.get_pixmap_width(), 4 * CELL);
+    assert_eq!(backend.font_size(), 32.0);
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::backend;
+use ratatui::Terminal;
+use ratatui::backend::Backend;
+use ratatui::layout::{Position, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{List, ListState, Paragraph};
+use soft_ratatui::SoftBackend;
+
+fn terminal(width: u16, height: u16) -> Terminal<SoftBackend> {
+    Terminal::new(backend(width, height)).unwrap()
+}
+
+#[test]
+fn lines_hold_the_visible_text() {
+    let mut terminal = terminal(12, 3);
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new("Hello\n  indented  \n"), frame.area()))
+        .unwrap();
+    let text = terminal.backend_mut().screen_text();
+    let lines: Vec<&str> = text.lines.iter().map(|line| line.text.as_str()).collect();
+    assert_eq!(lines, ["Hello", "  indented", ""]);
+    assert!(text.lines[2].runs.is_empty());
+}
+
+#[test]
+fn wide_characters_are_not_followed_by_spaces() {
+    let mut terminal = terminal(12, 1);
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new("日本 ok"), frame.area()))
+        .unwrap();
+    let text = terminal.backend_mut().screen_text();
+    assert_eq!(text.lines[0].text, "日本 ok");
+}
+
+#[test]
+fn hidden_text_reads_as_spaces() {
+    let mut terminal = terminal(16, 1);
+    let line = Line::from(vec!["user ".into(), "secret".hidden(), " end".into()]);
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new(line), frame.area()))
+        .unwrap();
+    assert_eq!(terminal.backend_mut().screen_text().lines[0].text, "user        end");
+}
+
+#[test]
+fn runs_split_where_the_style_changes() {
+    let mut terminal = terminal(10, 3);
+    let mut state = ListState::default().with_selected(Some(1));
+    terminal
+        .draw(|frame| {
+            let list = List::new(["one", "two", "three"]).highlight_style(Style::new().reversed());
+            frame.render_stateful_widget(list, frame.area(), &mut state);
+        })
+        .unwrap();
+    let text = terminal.backend_mut().screen_text();
+    assert_eq!(text.lines[0].runs.len(), 1);
+    let selected = &text.lines[1].runs[0];
+    assert_eq!((selected.column, selected.text.as_str()), (0, "two"));
+    assert!(selected.style.add_modifier.contains(Modifier::REVERSED));
+
+    let line = Line::from(vec!["ab".bold(), "cd".into()]);
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new(line), Rect::new(3, 0, 7, 1)))
+        .unwrap();
+    let runs = &terminal.backend_mut().screen_text().lines[0].runs;
+    let columns: Vec<(u16, &str)> = runs.iter().map(|run| (run.column, run.text.as_str())).collect();
+    assert_eq!(columns, [(0, "   "), (3, "ab"), (5, "cd")]);
+}
+
+#[test]
+fn changed_lines_since_the_previous_call() {
+    let mut terminal = terminal(10, 4);
+    assert_eq!(terminal.backend_mut().screen_text().changed_lines, [0, 1, 2, 3]);
+    assert!(terminal.backend_mut().screen_text().changed_lines.is_empty());
+
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new("x"), Rect::new(2, 2, 1, 1)))
+        .unwrap();
+    assert_eq!(terminal.backend_mut().screen_text().changed_lines, [2]);
+
+    // A style change without a text change is reported too.
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new("x".bold()), Rect::new(2, 2, 1, 1)))
+        .unwrap();
+    assert_eq!(terminal.backend_mut().screen_text().changed_lines, [2]);
+
+    terminal.backend_mut().clear().unwrap();
+    assert_eq!(terminal.backend_mut().screen_text().changed_lines, [2]);
+}
+
+#[test]
+fn cursor_position_follows_the_visible_cursor() {
+    let mut backend = backend(10, 4);
+    assert_eq!(backend.screen_text().cursor, None);
+    backend.set_cursor_position((3, 1)).unwrap();
+    backend.show_cursor().unwrap();
+    assert_eq!(backend.screen_text().cursor, Some(Position::new(3, 1)));
+    backend.set_cursor_position((30, 1)).unwrap();
+    assert_eq!(backend.screen_text().cursor, None);
+}
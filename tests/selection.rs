@@ -0,0 +1,126 @@
+mod common;
+
+use common::{CELL, backend, cell_is};
+use ratatui::Terminal;
+use ratatui::backend::Backend;
+use ratatui::layout::Position;
+use ratatui::widgets::Paragraph;
+use soft_ratatui::{Selection, SelectionMode, SoftBackend};
+
+const TEXT: &str = "first line\nsecond  line   \nthird line";
+
+fn terminal() -> Terminal<SoftBackend> {
+    let mut terminal = Terminal::new(backend(16, 4)).unwrap();
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new(TEXT), frame.area()))
+        .unwrap();
+    terminal
+}
+
+fn select(backend: &mut SoftBackend, start: (u16, u16), end: (u16, u16), mode: SelectionMode) {
+    let selection = Selection::new(start.into(), end.into(), mode);
+    backend.set_selection(Some(selection));
+}
+
+#[test]
+fn linear_selection_follows_the_text() {
+    let mut terminal = terminal();
+    let backend = terminal.backend_mut();
+    assert_eq!(backend.selected_text(), None);
+
+    select(backend, (6, 0), (5, 2), SelectionMode::Linear);
+    assert_eq!(backend.selected_text().unwrap(), "line\nsecond  line\nthird");
+    // Dragging backwards selects the same text.
+    select(backend, (5, 2), (6, 0), SelectionMode::Linear);
+    assert_eq!(backend.selected_text().unwrap(), "line\nsecond  line\nthird");
+}
+
+#[test]
+fn block_selection_is_rectangular() {
+    let mut terminal = terminal();
+    let backend = terminal.backend_mut();
+    select(backend, (9, 2), (2, 0), SelectionMode::Block);
+    assert_eq!(backend.selected_text().unwrap(), "rst line\ncond  li\nird line");
+}
+
+#[test]
+fn selection_is_drawn_reversed() {
+    let mut terminal = terminal();
+    let backend = terminal.backend_mut();
+    // Empty cells on the last row, reset colors are swapped.
+    select(backend, (1, 3), (2, 3), SelectionMode::Linear);
+    assert!(cell_is(backend, 0, 3, [24, 24, 24]));
+    assert!(cell_is(backend, 1, 3, [215, 215, 215]));
+    assert!(cell_is(backend, 2, 3, [215, 215, 215]));
+    assert!(cell_is(backend, 3, 3, [24, 24, 24]));
+
+    backend.set_selection(None);
+    assert!(cell_is(backend, 1, 3, [24, 24, 24]));
+    let partial = backend.rgb_pixmap.clone();
+    backend.redraw();
+    assert_eq!(partial.data(), backend.rgb_pixmap.data());
+}
+
+#[test]
+fn double_click_selects_a_word() {
+    let mut terminal = Terminal::new(backend(30, 1)).unwrap();
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new("open ~/src/main.rs (now)"), frame.area()))
+        .unwrap();
+    let backend = terminal.backend_mut();
+
+    backend.select_word(Position::new(8, 0)).unwrap();
+    assert_eq!(backend.selected_text().unwrap(), "~/src/main.rs");
+    backend.select_word(Position::new(20, 0)).unwrap();
+    assert_eq!(backend.selected_text().unwrap(), "now");
+    // Outside of words only the clicked cell is selected.
+    let selection = backend.select_word(Position::new(19, 0)).unwrap();
+    assert_eq!((selection.start, selection.end), (Position::new(19, 0), Position::new(19, 0)));
+    assert_eq!(backend.select_word(Position::new(30, 0)), None);
+}
+
+#[test]
+fn triple_click_selects_a_line() {
+    let mut terminal = terminal();
+    let backend = terminal.backend_mut();
+    backend.select_line(1).unwrap();
+    assert_eq!(backend.selected_text().unwrap(), "second  line");
+    assert_eq!(backend.select_line(4), None);
+}
+
+#[test]
+fn wide_characters_are_copied_once() {
+    let mut terminal = Terminal::new(backend(12, 1)).unwrap();
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new("a日本b"), frame.area()))
+        .unwrap();
+    let backend = terminal.backend_mut();
+    select(backend, (0, 0), (5, 0), SelectionMode::Linear);
+    assert_eq!(backend.selected_text().unwrap(), "a日本b");
+    // Selecting only the second half of a wide character still copies it.
+    select(backend, (2, 0), (3, 0), SelectionMode::Linear);
+    assert_eq!(backend.selected_text().unwrap(), "日本");
+    backend.select_word(Position::new(4, 0)).unwrap();
+    assert_eq!(backend.selected_text().unwrap(), "a日本b");
+}
+
+#[test]
+fn cell_at_pixel_maps_mouse_positions() {
+    let backend = backend(16, 4);
+    assert_eq!(backend.cell_at_pixel(0, 0), Some(Position::new(0, 0)));
+    assert_eq!(backend.cell_at_pixel(3 * CELL + 7, 2 * CELL), Some(Position::new(3, 2)));
+    assert_eq!(backend.cell_at_pixel(16 * CELL, 0), None);
+    assert_eq!(backend.cell_at_pixel(0, 4 * CELL), None);
+}
+
+#[test]
+fn selection_stays_drawn_after_clear() {
+    let mut terminal = terminal();
+    let backend = terminal.backend_mut();
+    select(backend, (0, 1), (3, 1), SelectionMode::Linear);
+    backend.clear().unwrap();
+    assert!(cell_is(backend, 2, 1, [215, 215, 215]));
+    let cleared = backend.rgb_pixmap.clone();
+    backend.redraw();
+    assert_eq!(cleared.data(), backend.rgb_pixmap.data());
+}
@@ -0,0 +1,167 @@
+//! Tests of the snapshot harness itself. They compare against golden images written by the tests,
+//! so they expect `SOFT_RATATUI_UPDATE_SNAPSHOTS` to be unset.
+#![cfg(feature = "testing")]
+
+use std::fs;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::{Path, PathBuf};
+
+use ratatui::Terminal;
+use ratatui::widgets::{Block, Paragraph};
+use soft_ratatui::testing::{Tolerance, assert_snapshot, from_ppm, test_backend, to_ppm};
+use soft_ratatui::{RgbPixmap, SoftBackend};
+
+/// Returns an empty directory for the snapshots of one test.
+fn snapshot_dir(test: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("snapshots").join(test);
+    _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn hello() -> Terminal<SoftBackend> {
+    let mut terminal = Terminal::new(test_backend(8, 3)).unwrap();
+    terminal
+        .draw(|frame| frame.render_widget(Paragraph::new("Hi").block(Block::bordered()), frame.area()))
+        .unwrap();
+    terminal
+}
+
+/// Stores `golden` as the snapshot `name` in `dir`.
+fn write_golden(dir: &Path, name: &str, golden: &RgbPixmap) {
+    fs::write(dir.join(format!("{name}.ppm")), to_ppm(golden)).unwrap();
+}
+
+/// Runs the assertion, returning its panic message if it failed.
+fn check(backend: &SoftBackend, dir: &Path, name: &str, tolerance: Tolerance) -> Option<String> {
+    let result = catch_unwind(AssertUnwindSafe(|| assert_snapshot(backend, dir, name, tolerance)));
+    result.err().map(|panic| {
+        panic
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_default()
+    })
+}
+
+/// Returns a copy of `pixmap` with `count` pixels starting at the top-left corner brightened by `delta`.
+fn brightened(pixmap: &RgbPixmap, count: usize, delta: u8) -> RgbPixmap {
+    let mut changed = pixmap.clone();
+    for index in 0..count {
+        let (x, y) = (index % pixmap.width(), index / pixmap.width());
+        let pixel = pixmap.get_pixel(x, y).map(|channel| channel.saturating_add(delta));
+        changed.put_pixel(x, y, pixel);
+    }
+    changed
+}
+
+#[test]
+fn ppm_round_trip() {
+    let terminal = hello();
+    let backend = terminal.backend();
+    let ppm = to_ppm(&backend.rgb_pixmap);
+    assert!(ppm.starts_with(format!("P6\n{} {}\n255\n", backend.get_pixmap_width(), backend.get_pixmap_height()).as_bytes()));
+    let decoded = from_ppm(&ppm).unwrap();
+    assert_eq!(decoded.width(), backend.rgb_pixmap.width());
+    assert_eq!(decoded.data(), backend.rgb_pixmap.data());
+
+    assert!(from_ppm(&ppm[..ppm.len() - 1]).is_none());
+    assert!(from_ppm(b"P3\n1 1\n255\n\x00\x00\x00").is_none());
+    assert!(from_ppm(b"P6\n1 1\n65535\n\x00\x00\x00").is_none());
+    assert!(from_ppm(b"P6\n1").is_none());
+    assert!(from_ppm(b"").is_none());
+}
+
+#[test]
+fn missing_snapshots_fail_without_being_written() {
+    let dir = snapshot_dir("missing");
+    let terminal = hello();
+    let backend = terminal.backend();
+    let message = check(backend, &dir, "hello", Tolerance::default()).expect("a missing snapshot must fail");
+    assert!(message.contains("is missing"), "{message}");
+    assert!(!dir.join("hello.ppm").exists());
+    // The rendering is left for review.
+    let actual = from_ppm(&fs::read(dir.join("hello.actual.ppm")).unwrap()).unwrap();
+    assert_eq!(actual.data(), backend.rgb_pixmap.data());
+    assert!(fs::read_to_string(dir.join("hello.actual.txt")).unwrap().contains("Hi"));
+}
+
+#[test]
+fn matching_snapshots_pass_and_clean_up() {
+    let dir = snapshot_dir("matching");
+    let terminal = hello();
+    let backend = terminal.backend();
+    write_golden(&dir, "hello", &brightened(&backend.rgb_pixmap, 1, 100));
+    assert!(check(backend, &dir, "hello", Tolerance::default()).is_some());
+    assert!(dir.join("hello.actual.ppm").exists());
+
+    write_golden(&dir, "hello", &backend.rgb_pixmap);
+    assert_eq!(check(backend, &dir, "hello", Tolerance::default()), None);
+    for stale in ["hello.actual.ppm", "hello.diff.ppm", "hello.actual.txt"] {
+        assert!(!dir.join(stale).exists(), "{stale}");
+    }
+}
+
+#[test]
+fn channel_tolerance() {
+    let dir = snapshot_dir("channel");
+    let terminal = hello();
+    let backend = terminal.backend();
+    write_golden(&dir, "hello", &brightened(&backend.rgb_pixmap, 20, 2));
+    assert_eq!(check(backend, &dir, "hello", Tolerance::default()), None);
+    let strict = Tolerance { channel: 1, ..Tolerance::default() };
+    let message = check(backend, &dir, "hello", strict).expect("deltas over the channel tolerance must fail");
+    assert!(message.contains("20 pixels differ by up to 2"), "{message}");
+}
+
+#[test]
+fn pixel_tolerance() {
+    let dir = snapshot_dir("pixels");
+    let terminal = hello();
+    let backend = terminal.backend();
+    write_golden(&dir, "hello", &brightened(&backend.rgb_pixmap, 3, 50));
+    let two = Tolerance { pixels: 2, ..Tolerance::default() };
+    assert!(check(backend, &dir, "hello", two).is_some());
+    let three = Tolerance { pixels: 3, ..Tolerance::default() };
+    assert_eq!(check(backend, &dir, "hello", three), None);
+}
+
+#[test]
+fn ssim_tolerance() {
+    let dir = snapshot_dir("ssim");
+    let terminal = hello();
+    let backend = terminal.backend();
+    let golden = brightened(&backend.rgb_pixmap, backend.rgb_pixmap.width() * 4, 6);
+    write_golden(&dir, "hello", &golden);
+    let ssim = golden.diff(&backend.rgb_pixmap).ssim();
+    assert!(ssim < 1.0);
+
+    assert!(check(backend, &dir, "hello", Tolerance::default()).is_some());
+    let similar = Tolerance { min_ssim: Some(ssim), ..Tolerance::default() };
+    assert_eq!(check(backend, &dir, "hello", similar), None);
+    let stricter = Tolerance { min_ssim: Some(ssim + 0.001), ..Tolerance::default() };
+    assert!(check(backend, &dir, "hello", stricter).is_some());
+}
+
+#[test]
+fn failures_write_a_diff_image() {
+    let dir = snapshot_dir("diff");
+    let terminal = hello();
+    let backend = terminal.backend();
+    write_golden(&dir, "hello", &brightened(&backend.rgb_pixmap, 3, 50));
+    let message = check(backend, &dir, "hello", Tolerance::default()).unwrap();
+    assert!(message.contains("3 pixels differ by up to 50 within 3x1 at (0, 0)"), "{message}");
+
+    let diff = from_ppm(&fs::read(dir.join("hello.diff.ppm")).unwrap()).unwrap();
+    assert_eq!((diff.width(), diff.height()), (backend.rgb_pixmap.width(), backend.rgb_pixmap.height()));
+    for x in 0..3 {
+        assert_eq!(diff.get_pixel(x, 0), [255, 0, 0]);
+    }
+    let [r, g, b] = diff.get_pixel(3, 0);
+    assert!(r == g && g == b, "unchanged pixels are gray");
+
+    // A size change fails without a diff image.
+    write_golden(&dir, "hello", &RgbPixmap::new(4, 4));
+    let message = check(backend, &dir, "hello", Tolerance::default()).unwrap();
+    assert!(message.contains("differs from the snapshot size 4x4"), "{message}");
+    assert!(!dir.join("hello.diff.ppm").exists());
+}
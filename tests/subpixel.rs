@@ -0,0 +1,81 @@
+//! Subpixel antialiasing gives glyph edges one coverage value per color channel.
+#![cfg(feature = "cosmic")]
+
+use ratatui::backend::Backend;
+use ratatui::buffer::Cell;
+use ratatui::style::{Color, Style};
+use soft_ratatui::{Antialiasing, SoftBackend, SubpixelOrder};
+
+static FONT_DATA: &[u8] = include_bytes!("../assets/fm.ttf");
+
+/// Draws a white "H" on black with the given antialiasing.
+fn draw(antialiasing: Antialiasing) -> Vec<[u8; 3]> {
+    draw_with(Color::Rgb(0, 0, 0), |backend| backend.set_antialiasing(antialiasing))
+}
+
+/// Draws a white "H" on the given background, after `setup` configured the backend.
+fn draw_with(background: Color, setup: impl FnOnce(&mut SoftBackend)) -> Vec<[u8; 3]> {
+    let mut backend = SoftBackend::new_with_font(1, 1, 16.0, FONT_DATA);
+    setup(&mut backend);
+    let mut cell = Cell::new("H");
+    cell.set_style(Style::new().fg(Color::Rgb(255, 255, 255)).bg(background));
+    backend.draw([(0, 0, &cell)].into_iter()).unwrap();
+    backend.get_pixmap_data().chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect()
+}
+
+fn is_gray(pixel: &[u8; 3]) -> bool {
+    pixel[0] == pixel[1] && pixel[1] == pixel[2]
+}
+
+#[test]
+fn grayscale_has_no_color_fringes() {
+    let pixels = draw(Antialiasing::Grayscale);
+    assert!(pixels.iter().all(is_gray));
+    assert!(pixels.iter().any(|pixel| pixel[0] > 0 && pixel[0] < 255));
+}
+
+#[test]
+fn subpixel_colors_glyph_edges() {
+    for order in [SubpixelOrder::Rgb, SubpixelOrder::Bgr] {
+        let pixels = draw(Antialiasing::Subpixel(order));
+        assert!(pixels.iter().any(|pixel| !is_gray(pixel)), "{order:?}");
+        // The background and the inside of strokes stay neutral.
+        assert!(pixels.contains(&[0, 0, 0]));
+        assert!(pixels.contains(&[255, 255, 255]), "{order:?}");
+    }
+}
+
+#[test]
+fn bgr_swaps_the_fringes_of_rgb() {
+    let rgb = draw(Antialiasing::Subpixel(SubpixelOrder::Rgb));
+    let bgr = draw(Antialiasing::Subpixel(SubpixelOrder::Bgr));
+    assert_ne!(rgb, bgr);
+    for (rgb, bgr) in rgb.iter().zip(&bgr) {
+        assert_eq!(*rgb, [bgr[2], bgr[1], bgr[0]]);
+    }
+}
+
+#[test]
+fn subpixel_falls_back_to_grayscale() {
+    let subpixel = |backend: &mut SoftBackend| backend.set_antialiasing(Antialiasing::Subpixel(SubpixelOrder::Rgb));
+    let grayscale = draw_with(Color::Reset, |backend| backend.set_antialiasing(Antialiasing::Grayscale));
+    assert_ne!(draw_with(Color::Reset, subpixel), grayscale);
+
+    // Scaled or rotated output, and backgrounds the host shows through.
+    let transformed = draw_with(Color::Reset, |backend| {
+        subpixel(backend);
+        backend.set_output_transformed(true);
+    });
+    assert_eq!(transformed, grayscale);
+    let transparent = draw_with(Color::Reset, |backend| {
+        subpixel(backend);
+        backend.set_reset_background_transparent(true);
+    });
+    assert_eq!(transparent, grayscale);
+    // Only cells with the reset background are transparent.
+    let opaque = draw_with(Color::Rgb(0, 0, 0), |backend| {
+        subpixel(backend);
+        backend.set_reset_background_transparent(true);
+    });
+    assert!(opaque.iter().any(|pixel| !is_gray(pixel)));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{cell_is, draw_cell, pixel};
+use ratatui::style::{Color, Modifier, Style};
+use soft_ratatui::{RgbPixmap, SoftBackend, Tileset, TilesetError};
+
+const KEY: [u8; 3] = [255, 0, 255];
+const GRAY: [u8; 3] = [128, 128, 128];
+const RED: [u8; 3] = [255, 0, 0];
+const BLUE: [u8; 3] = [0, 0, 255];
+
+/// A CP437 sheet of 8x8 tiles where `A` is a gray square with a transparent border, every other tile is empty.
+fn tileset_backend() -> SoftBackend {
+    let mut sheet = RgbPixmap::new(16 * 8, 16 * 8);
+    for y in 0..sheet.height() {
+        for x in 0..sheet.width() {
+            sheet.put_pixel(x, y, KEY);
+        }
+    }
+    let index = 'A' as usize;
+    let (left, top) = (index % 16 * 8, index / 16 * 8);
+    for y in 1..7 {
+        for x in 1..7 {
+            sheet.put_pixel(left + x, top + y, GRAY);
+        }
+    }
+    let tileset = Tileset::from_pixmap(&sheet, 8, 8, KEY).unwrap();
+    SoftBackend::new_with_tileset(4, 2, tileset, 1.0)
+}
+
+fn style(modifier: Modifier) -> Style {
+    Style::new().fg(Color::Rgb(255, 0, 0)).bg(Color::Rgb(0, 0, 255)).add_modifier(modifier)
+}
+
+#[test]
+fn tiles_are_tinted_with_the_foreground() {
+    let mut backend = tileset_backend();
+    draw_cell(&mut backend, 1, 0, "A", style(Modifier::empty()));
+    assert_eq!(pixel(&backend, 1, 0, 0, 0), BLUE);
+    assert_eq!(pixel(&backend, 1, 0, 3, 3), [128, 0, 0]);
+    assert_ne!(pixel(&backend, 1, 0, 3, 3), RED);
+}
+
+#[test]
+fn hidden_tiles_show_only_the_background() {
+    let mut backend = tileset_backend();
+    draw_cell(&mut backend, 1, 0, "A", style(Modifier::HIDDEN));
+    assert!(cell_is(&backend, 1, 0, BLUE));
+    draw_cell(&mut backend, 1, 0, "A", style(Modifier::HIDDEN | Modifier::REVERSED));
+    assert!(cell_is(&backend, 1, 0, RED));
+}
+
+#[test]
+fn blinking_tiles_disappear_in_the_off_phase() {
+    let mut backend = tileset_backend();
+    draw_cell(&mut backend, 1, 0, "A", style(Modifier::SLOW_BLINK));
+    assert!(!cell_is(&backend, 1, 0, BLUE));
+    assert!(backend.tick(Duration::from_millis(600)));
+    assert!(cell_is(&backend, 1, 0, BLUE));
+    assert!(backend.tick(Duration::from_millis(500)));
+    assert_eq!(pixel(&backend, 1, 0, 3, 3), [128, 0, 0]);
+}
+
+#[test]
+fn malformed_sheets_are_rejected() {
+    let sheet = RgbPixmap::new(20, 20);
+    assert_eq!(Tileset::from_pixmap(&sheet, 8, 8, KEY).unwrap_err(), TilesetError::InvalidTileSize);
+    assert_eq!(Tileset::from_pixmap(&sheet, 0, 10, KEY).unwrap_err(), TilesetError::InvalidTileSize);
+    assert_eq!(
+        Tileset::from_rgba(&[0; 4 * 20 * 19], 20, 20, 10, 10).unwrap_err(),
+        TilesetError::InvalidSheetSize
+    );
+    assert_eq!(
+        Tileset::from_rgba(&[0; 16], usize::MAX, 1, 1, 1).unwrap_err(),
+        TilesetError::InvalidSheetSize
+    );
+}